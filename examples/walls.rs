@@ -152,11 +152,13 @@ fn collide_player_ball(
     mut collisions: EventReader<CollisionEvent>,
 ) {
     for collision in collisions.iter() {
-        let CollisionEvent::Began(CollisionBegan { us, them , contact}) = collision else { continue; };
+        let CollisionEvent::Began(CollisionBegan { us, them, contact, .. }) = collision else { continue; };
 
-        let ContactManifold::Point(contact) = contact else { unreachable!() };
+        // Edge manifolds have a pair of contact points, so resolve the deepest of them
+        let Some(mut contact) = contact
+            .contact_points()
+            .max_by(|a, b| a.depth().total_cmp(&b.depth())) else { continue; };
 
-        let mut contact = contact;
         let player;
         let ball;
 
@@ -177,8 +179,8 @@ fn collide_player_ball(
         let (mut ball_transform, mut ball_velocity) = ball;
 
         let deintersect_vector = contact.separation_vector();
-        ball_transform.translation += deintersect_vector;
-        ball_velocity += deintersect_vector;
+        ball_transform.translation += deintersect_vector.extend(0.0);
+        ball_velocity.0 += deintersect_vector;
     }
 }
 
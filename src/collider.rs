@@ -2,27 +2,47 @@ mod algorithms;
 mod capsule;
 mod circle;
 mod contact;
+mod convex_polygon;
+mod distance;
+mod gjk;
 mod line;
+mod offset;
 mod point;
 mod polygon;
+mod ray;
 mod rect;
+mod shape;
+mod simplify;
+mod sweep;
 mod triangle;
+mod visibility;
 
 use bevy::{
-    prelude::{Component, Entity, GlobalTransform, Rect, Vec2},
+    prelude::{Component, Entity, GlobalTransform, Quat, Rect, Resource, Transform, Vec2},
     utils::HashMap,
 };
 use bevy_prototype_lyon::prelude::{tess::path::path::Builder, Geometry};
+use std::sync::Arc;
 
 pub use {
     capsule::Capsule,
     circle::Circle,
-    contact::{ContactEdge, ContactManifold, ContactPoint},
+    contact::{ContactEdge, ContactId, ContactManifold, ContactPoint, FeatureId},
+    convex_polygon::ConvexPolygon,
+    distance::DistanceResult,
     line::{Line, LineIntersection},
+    offset::JoinStyle,
     point::Point,
     polygon::Polygon,
+    ray::{Ray2d, RayHit},
     rect::Rectangle,
+    shape::{Shape, TransformedShape},
+    simplify::{
+        douglas_peucker, douglas_peucker_closed, visvalingam_whyatt, visvalingam_whyatt_closed,
+    },
+    sweep::{ShapeHit, Sweep, TimeOfImpact},
     triangle::Triangle,
+    visibility::visibility_polygon,
 };
 
 pub trait Transformable {
@@ -36,21 +56,174 @@ pub trait Collides<T>: Transformable {
 #[derive(Component, Clone, Default, Debug, PartialEq)]
 pub struct Colliding(pub HashMap<Entity, ContactManifold>);
 
+/// Offsets the [`Collider`] of an entity relative to the entity itself, without having to edit the shape or
+/// spawn a child entity.
+///
+/// The offset is applied in the local space of the entity, before its [`GlobalTransform`].
+#[derive(Component, Clone, Copy, Default, Debug, PartialEq)]
+pub struct ColliderOffset {
+    pub translation: Vec2,
+    /// The anticlockwise rotation, in radians.
+    pub rotation: f32,
+}
+
+impl ColliderOffset {
+    pub fn new(translation: Vec2, rotation: f32) -> Self {
+        Self {
+            translation,
+            rotation,
+        }
+    }
+
+    pub fn from_translation(translation: Vec2) -> Self {
+        Self::new(translation, 0.0)
+    }
+
+    pub fn from_rotation(rotation: f32) -> Self {
+        Self::new(Vec2::ZERO, rotation)
+    }
+
+    pub fn to_transform(&self) -> Transform {
+        Transform::from_translation(self.translation.extend(0.0))
+            .with_rotation(Quat::from_rotation_z(self.rotation))
+    }
+
+    /// The world space transform of a collider with this offset, attached to an entity with the given transform.
+    pub fn apply(&self, transform: &GlobalTransform) -> GlobalTransform {
+        transform.mul_transform(self.to_transform())
+    }
+}
+
+/// Extends the range at which a collider reports contacts beyond its outline, so that pairs that are about to
+/// collide produce speculative contacts with a negative [`ContactManifold::depth`].
+///
+/// The margin for a pair of colliders is the sum of both of their skins. Insert it as a resource to set the skin
+/// of every collider that doesn't have its own.
+#[derive(Component, Resource, Clone, Copy, Default, Debug, PartialEq)]
+pub struct ContactSkin(pub f32);
+
+/// Enables continuous collision detection for a collider, so that it can't tunnel through other colliders when
+/// it moves a long way in a single frame.
+///
+/// Each frame, the collider is swept from its transform in the previous frame to its current one, and the time
+/// of impact is reported in [`CollisionBegan`](crate::plugin::CollisionBegan).
+#[derive(Component, Clone, Copy, Default, Debug, PartialEq)]
+pub struct Ccd {
+    pub(crate) previous: Option<GlobalTransform>,
+}
+
 #[derive(Clone, Component, Debug, PartialEq)]
 pub enum Collider {
     Capsule(Capsule),
     Circle(Circle),
+    ConvexPolygon(ConvexPolygon),
     //Line(Line),
     Point(Point),
     Polygon(Polygon),
     Rectangle(Rectangle),
     //Triangle(Triangle),
+    /// A user-defined shape, see [`Shape`].
+    Custom(Arc<dyn Shape>),
 }
 
 impl Collider {
     pub fn new(collider: impl Into<Self>) -> Self {
         collider.into()
     }
+
+    pub fn custom(shape: impl Shape) -> Self {
+        Self::Custom(Arc::new(shape))
+    }
+
+    /// Moves `self` into world space, taking the offset of the collider (if any) into account.
+    pub fn to_world(&self, transform: &GlobalTransform, offset: Option<&ColliderOffset>) -> Self {
+        match offset {
+            Some(offset) => self.to_transformed(&offset.apply(transform)),
+            None => self.to_transformed(transform),
+        }
+    }
+
+    /// Like [`Collides::collide`], but also returns a speculative contact with a negative depth when `self` and
+    /// `other` are within `margin` of each other without overlapping.
+    pub fn collide_with_margin(&self, other: &Collider, margin: f32) -> Option<ContactManifold> {
+        if margin <= 0.0 {
+            return self.collide(other);
+        }
+
+        if distance::aabbs_are_further_than(self, other, margin) {
+            return None;
+        }
+
+        self.collide(other).or_else(|| {
+            distance::separation(self, other)
+                .filter(|manifold| manifold.depth() >= -margin)
+                .map(|manifold| manifold.with_features(self, other))
+        })
+    }
+
+    /// The distance between `self` and `other`, along with the closest point on each of them, once they have been
+    /// moved into world space by their transforms.
+    pub fn distance(
+        &self,
+        transform: &GlobalTransform,
+        other: &Collider,
+        other_transform: &GlobalTransform,
+    ) -> DistanceResult {
+        distance::distance(
+            &self.to_transformed(transform),
+            &other.to_transformed(other_transform),
+        )
+    }
+
+    /// The closest point on the outline of `self` to `point`, whether or not `point` lies inside `self`, and the
+    /// feature of `self` that the closest point lies on.
+    pub fn project_point(&self, point: Vec2) -> (Vec2, bool, FeatureId) {
+        distance::project_point(self, point)
+    }
+
+    /// The vertices around the outline of `self`, with curved shapes approximated by polygons.
+    pub fn outline(&self) -> Vec<Vec2> {
+        visibility::outline(self)
+    }
+
+    /// The first point at which `ray` hits `self` once it has been moved into world space by `transform`, within
+    /// `max_toi`.
+    ///
+    /// If `ray` starts inside `self`, it hits immediately when `solid` is true, and otherwise hits the outline of
+    /// `self` on its way out.
+    pub fn cast_ray(
+        &self,
+        transform: &GlobalTransform,
+        ray: &Ray2d,
+        max_toi: f32,
+        solid: bool,
+    ) -> Option<RayHit> {
+        Shape::cast_ray(&self.to_transformed(transform), ray, max_toi, solid)
+    }
+
+    /// Moves `self` from `transform` by `velocity`, and finds the first point at which it hits `other`.
+    ///
+    /// If `self` and `other` already overlap at the start, they hit at a time of impact of 0.
+    pub fn cast_shape(
+        &self,
+        transform: &GlobalTransform,
+        velocity: Vec2,
+        other: &Collider,
+        other_transform: &GlobalTransform,
+    ) -> Option<ShapeHit> {
+        sweep::cast_shape(self, transform, velocity, other, other_transform)
+    }
+
+    /// Finds the first time at which `self` and `other` touch as they move along the given sweeps, if they touch
+    /// at all. `self` and `other` must be in local space, as they are moved by their sweeps.
+    pub fn time_of_impact(
+        &self,
+        sweep: &Sweep,
+        other: &Collider,
+        other_sweep: &Sweep,
+    ) -> Option<TimeOfImpact> {
+        sweep::time_of_impact(self, sweep, other, other_sweep)
+    }
 }
 
 impl Default for Collider {
@@ -71,6 +244,12 @@ impl From<Circle> for Collider {
     }
 }
 
+impl From<ConvexPolygon> for Collider {
+    fn from(polygon: ConvexPolygon) -> Self {
+        Self::ConvexPolygon(polygon)
+    }
+}
+
 // impl From<Line> for Collider {
 //     fn from(line: Line) -> Self {
 //         Self::Line(line)
@@ -107,6 +286,12 @@ impl From<Rect> for Collider {
     }
 }
 
+impl From<Arc<dyn Shape>> for Collider {
+    fn from(shape: Arc<dyn Shape>) -> Self {
+        Self::Custom(shape)
+    }
+}
+
 // impl From<Triangle> for Collider {
 //     fn from(triangle: Triangle) -> Self {
 //         Self::Triangle(triangle)
@@ -118,20 +303,91 @@ impl Transformable for Collider {
         match self {
             Collider::Capsule(shape) => shape.to_transformed(transform).into(),
             Collider::Circle(shape) => shape.to_transformed(transform).into(),
+            Collider::ConvexPolygon(shape) => shape.to_transformed(transform).into(),
             // Collider::Line(shape) => shape.to_transformed(transform).into(),
             Collider::Point(shape) => shape.to_transformed(transform).into(),
             Collider::Polygon(shape) => shape.to_transformed(transform).into(),
             Collider::Rectangle(shape) => shape.to_transformed(transform).into(),
             // Collider::Triangle(shape) => shape.to_transformed(transform).into(),
+            Collider::Custom(shape) => shape.to_transformed(transform).into(),
         }
     }
 }
 
+impl Shape for Collider {
+    fn support_point(&self, direction: Vec2) -> Vec2 {
+        match self {
+            Collider::Capsule(shape) => shape.support_point(direction),
+            // Not `Circle::support_point`, which requires a normalized direction
+            Collider::Circle(shape) => Shape::support_point(shape, direction),
+            Collider::ConvexPolygon(shape) => shape.support_point(direction),
+            Collider::Point(shape) => shape.support_point(direction),
+            Collider::Polygon(shape) => shape.support_point(direction),
+            Collider::Rectangle(shape) => shape.support_point(direction),
+            Collider::Custom(shape) => shape.support_point(direction),
+        }
+    }
+
+    fn aabb(&self) -> Rectangle {
+        match self {
+            Collider::Capsule(shape) => shape.aabb(),
+            Collider::Circle(shape) => shape.aabb(),
+            Collider::ConvexPolygon(shape) => shape.aabb(),
+            Collider::Point(shape) => shape.aabb(),
+            Collider::Polygon(shape) => shape.aabb(),
+            Collider::Rectangle(shape) => shape.aabb(),
+            Collider::Custom(shape) => shape.aabb(),
+        }
+    }
+
+    fn contains(&self, point: Vec2) -> bool {
+        match self {
+            Collider::Capsule(shape) => shape.contains(point),
+            Collider::Circle(shape) => shape.contains(point),
+            Collider::ConvexPolygon(shape) => shape.contains(point),
+            Collider::Point(shape) => shape.contains(point),
+            Collider::Polygon(shape) => shape.contains(point),
+            Collider::Rectangle(shape) => shape.contains(point),
+            Collider::Custom(shape) => shape.contains(point),
+        }
+    }
+
+    fn feature_at(&self, point: Vec2) -> FeatureId {
+        match self {
+            Collider::Capsule(shape) => shape.feature_at(point),
+            Collider::Circle(shape) => shape.feature_at(point),
+            Collider::ConvexPolygon(shape) => shape.feature_at(point),
+            Collider::Point(shape) => shape.feature_at(point),
+            Collider::Polygon(shape) => shape.feature_at(point),
+            Collider::Rectangle(shape) => shape.feature_at(point),
+            Collider::Custom(shape) => shape.feature_at(point),
+        }
+    }
+
+    fn cast_ray(&self, ray: &Ray2d, max_toi: f32, solid: bool) -> Option<RayHit> {
+        match self {
+            Collider::Capsule(shape) => shape.cast_ray(ray, max_toi, solid),
+            Collider::Circle(shape) => shape.cast_ray(ray, max_toi, solid),
+            Collider::ConvexPolygon(shape) => shape.cast_ray(ray, max_toi, solid),
+            Collider::Point(shape) => shape.cast_ray(ray, max_toi, solid),
+            Collider::Polygon(shape) => shape.cast_ray(ray, max_toi, solid),
+            Collider::Rectangle(shape) => shape.cast_ray(ray, max_toi, solid),
+            Collider::Custom(shape) => shape.cast_ray(ray, max_toi, solid),
+        }
+    }
+
+    #[cfg(feature = "debug-draw")]
+    fn debug_geometry(&self, b: &mut Builder) {
+        self.add_geometry(b);
+    }
+}
+
 impl Collides<Collider> for Collider {
     fn collide(&self, other: &Collider) -> Option<ContactManifold> {
-        match (self, other) {
+        let manifold = match (self, other) {
             (Collider::Capsule(a), Collider::Capsule(b)) => a.collide(b),
             (Collider::Capsule(a), Collider::Circle(b)) => a.collide(b),
+            (Collider::Capsule(a), Collider::ConvexPolygon(b)) => a.collide(b),
             // (Collider::Capsule(a), Collider::Line(b)) => a.collide(b),
             (Collider::Capsule(a), Collider::Point(b)) => a.collide(b),
             (Collider::Capsule(a), Collider::Polygon(b)) => a.collide(b),
@@ -139,11 +395,18 @@ impl Collides<Collider> for Collider {
             // (Collider::Capsule(a), Collider::Triangle(b)) => a.collide(b),
             (Collider::Circle(a), Collider::Capsule(b)) => a.collide(b),
             (Collider::Circle(a), Collider::Circle(b)) => a.collide(b),
+            (Collider::Circle(a), Collider::ConvexPolygon(b)) => a.collide(b),
             // (Collider::Circle(a), Collider::Line(b)) => a.collide(b),
             (Collider::Circle(a), Collider::Point(b)) => a.collide(b),
             (Collider::Circle(a), Collider::Polygon(b)) => a.collide(b),
             (Collider::Circle(a), Collider::Rectangle(b)) => a.collide(b),
             // (Collider::Circle(a), Collider::Triangle(b)) => a.collide(b),
+            (Collider::ConvexPolygon(a), Collider::Capsule(b)) => a.collide(b),
+            (Collider::ConvexPolygon(a), Collider::Circle(b)) => a.collide(b),
+            (Collider::ConvexPolygon(a), Collider::ConvexPolygon(b)) => a.collide(b),
+            (Collider::ConvexPolygon(a), Collider::Point(b)) => a.collide(b),
+            (Collider::ConvexPolygon(a), Collider::Polygon(b)) => a.collide(b),
+            (Collider::ConvexPolygon(a), Collider::Rectangle(b)) => a.collide(b),
             // (Collider::Line(a), Collider::Capsule(b)) => a.collide(b),
             // (Collider::Line(a), Collider::Circle(b)) => a.collide(b),
             // (Collider::Line(a), Collider::Line(b)) => a.collide(b),
@@ -153,6 +416,7 @@ impl Collides<Collider> for Collider {
             // (Collider::Line(a), Collider::Triangle(b)) => a.collide(b),
             (Collider::Point(a), Collider::Capsule(b)) => a.collide(b),
             (Collider::Point(a), Collider::Circle(b)) => a.collide(b),
+            (Collider::Point(a), Collider::ConvexPolygon(b)) => a.collide(b),
             // (Collider::Point(a), Collider::Line(b)) => a.collide(b),
             (Collider::Point(a), Collider::Point(b)) => a.collide(b),
             (Collider::Point(a), Collider::Polygon(b)) => a.collide(b),
@@ -160,6 +424,7 @@ impl Collides<Collider> for Collider {
             // (Collider::Point(a), Collider::Triangle(b)) => a.collide(b),
             (Collider::Polygon(a), Collider::Capsule(b)) => a.collide(b),
             (Collider::Polygon(a), Collider::Circle(b)) => a.collide(b),
+            (Collider::Polygon(a), Collider::ConvexPolygon(b)) => a.collide(b),
             // (Collider::Polygon(a), Collider::Line(b)) => a.collide(b),
             (Collider::Polygon(a), Collider::Point(b)) => a.collide(b),
             (Collider::Polygon(a), Collider::Polygon(b)) => a.collide(b),
@@ -167,6 +432,7 @@ impl Collides<Collider> for Collider {
             // (Collider::Polygon(a), Collider::Triangle(b)) => a.collide(b),
             (Collider::Rectangle(a), Collider::Capsule(b)) => a.collide(b),
             (Collider::Rectangle(a), Collider::Circle(b)) => a.collide(b),
+            (Collider::Rectangle(a), Collider::ConvexPolygon(b)) => a.collide(b),
             // (Collider::Rectangle(a), Collider::Line(b)) => a.collide(b),
             (Collider::Rectangle(a), Collider::Point(b)) => a.collide(b),
             (Collider::Rectangle(a), Collider::Polygon(b)) => a.collide(b),
@@ -179,7 +445,17 @@ impl Collides<Collider> for Collider {
             // (Collider::Triangle(a), Collider::Polygon(b)) => a.collide(b),
             // (Collider::Triangle(a), Collider::Rect(b)) => a.collide(b),
             // (Collider::Triangle(a), Collider::Triangle(b)) => a.collide(b),
-        }
+            (Collider::Custom(a), Collider::Polygon(b)) => {
+                algorithms::collide_shape_polygon(a.as_ref(), b)
+            }
+            (Collider::Polygon(a), Collider::Custom(b)) => {
+                algorithms::collide_shape_polygon(b.as_ref(), a).map(|manifold| -manifold)
+            }
+            (Collider::Custom(a), b) => algorithms::collide_shapes(a.as_ref(), b),
+            (a, Collider::Custom(b)) => algorithms::collide_shapes(a, b.as_ref()),
+        };
+
+        manifold.map(|manifold| manifold.with_features(self, other))
     }
 }
 
@@ -189,11 +465,13 @@ impl Geometry for Collider {
         match self {
             Collider::Capsule(shape) => shape.add_geometry(b),
             Collider::Circle(shape) => shape.add_geometry(b),
+            Collider::ConvexPolygon(shape) => shape.add_geometry(b),
             // Collider::Line(shape) => shape.add_geometry(b),
             Collider::Point(shape) => shape.add_geometry(b),
             Collider::Polygon(shape) => shape.add_geometry(b),
             Collider::Rectangle(shape) => shape.add_geometry(b),
             // Collider::Triangle(shape) => shape.add_geometry(b),
+            Collider::Custom(shape) => shape.debug_geometry(b),
         }
     }
 }
@@ -3,29 +3,27 @@ use super::*;
 pub fn collide_capsule_capsule(us: &Capsule, them: &Capsule) -> Option<ContactManifold> {
     let closest_points = us.line.closest_point_to_line(&them.line);
     if closest_points.is_point() {
-        // The inner lines of the capsules are intersecting
-        Some(ContactManifold::coincident(closest_points.start))
+        // The inner lines of the capsules are intersecting, so there are no closest points to push apart
+        collide_shapes(us, them)
     } else {
         let diff = closest_points.as_difference();
         if diff.length_squared() <= (us.radius + them.radius).powi(2) {
-            if us.line.is_parallel_to(&them.line) {
-                if let Some(us_clipped) = us.line.clip_to_parallel_line(&them.line) {
-                    if let Some(them_clipped) = them.line.clip_to_parallel_line(&us.line) {
-                        // The capsules are parallel
-                        return Some(ContactManifold::edge(
-                            us_clipped,
-                            them_clipped,
-                            diff.normalize(),
-                        ));
-                    }
+            let normal = diff.normalize();
+            let has_length = !us.line.is_point() && !them.line.is_point();
+            if has_length && us.line.is_nearly_parallel_to(&them.line) {
+                let manifold =
+                    parallel_line_contact(&us.line, us.radius, &them.line, them.radius, normal);
+                if let Some(manifold) = manifold {
+                    // The capsules are lying alongside each other
+                    return Some(manifold);
                 }
             }
 
             // Non-parallel capsules can be treated like a pair of circles around the closest points
             Some(ContactManifold::point(
-                closest_points.start + diff.clamp_length(us.radius, us.radius),
-                closest_points.end - diff.clamp_length(them.radius, them.radius),
-                diff.normalize(),
+                closest_points.start + (normal * us.radius),
+                closest_points.end - (normal * them.radius),
+                normal,
             ))
         } else {
             None
@@ -38,84 +36,402 @@ pub fn collide_capsule_circle(us: &Capsule, them: &Circle) -> Option<ContactMani
     let diff = them.position - closest_point;
 
     if diff.length_squared() <= (us.radius + them.radius).powi(2) {
+        // When the circle is centered on the inner line, push it out to the side
+        let normal = diff
+            .try_normalize()
+            .or_else(|| us.line.perp().try_normalize())
+            .unwrap_or(Vec2::Y);
         Some(ContactManifold::point(
-            closest_point + diff.clamp_length(us.radius, us.radius),
-            them.position - diff.clamp_length(them.radius, them.radius),
-            diff.normalize(),
+            closest_point + (normal * us.radius),
+            them.position - (normal * them.radius),
+            normal,
         ))
     } else {
         None
     }
 }
 
-// pub fn collide_capsule_line(us: &Capsule, them: &Line) -> ContactManifold {
-//     let closest_points = us.line.closest_point_to_line(them);
-//     if closest_points.length_squared() <= us.radius_squared() {
-//         if closest_points.is_point() {
-//             ContactManifold::Coincident(closest_points.start).into()
-//         } else if us.line.is_parallel_to(them) {
-//             ContactEdge::new().into()
-//         } else {
-//             ContactPoint::new(
-//                 closest_points.start + closest_points.as_difference().clamp_length_min(us.radius),
-//                 closest_points.end,
-//                 closest_points.as_difference().normalize(),
-//             )
-//             .into()
-//         }
-//     } else {
-//         ContactManifold::Disjoint
-//     }
-// }
+pub fn collide_capsule_line(us: &Capsule, them: &Line) -> Option<ContactManifold> {
+    collide_capsule_capsule(us, &Capsule::new(*them, 0.0))
+}
 
 pub fn collide_capsule_point(us: &Capsule, them: &Point) -> Option<ContactManifold> {
-    if us.line.distance_to_point_squared(&them.0) <= us.radius_squared() {
-        Some(ContactManifold::Coincident(them.0))
-    } else {
-        None
+    collide_capsule_circle(us, &Circle::new(them.0, 0.0))
+}
+
+pub fn collide_capsule_polygon(us: &Capsule, them: &Polygon) -> Option<ContactManifold> {
+    let core_overlaps = them.contains(us.line.start)
+        || them.contains(us.line.end)
+        || them.edges().any(|edge| edge.intersects_line(&us.line));
+
+    if core_overlaps {
+        // Push the capsule out from the point on its inner line that is deepest inside the polygon
+        let deepest = deepest_point_in_polygon(&us.line, them);
+        return collide_circle_polygon(&Circle::new(deepest, us.radius), them);
+    }
+
+    // Otherwise only the rounded part of the capsule can be touching, so find the closest features
+    let (edge, closest_points) = closest_edge_to_line(&us.line, them.edges())?;
+
+    let diff = closest_points.as_difference();
+    if diff.length_squared() > us.radius_squared() {
+        return None;
+    }
+
+    let normal = diff.normalize();
+    if us.line.is_nearly_parallel_to(&edge) {
+        if let Some(manifold) = parallel_line_contact(&us.line, us.radius, &edge, 0.0, normal) {
+            return Some(manifold);
+        }
+    }
+
+    Some(ContactManifold::point(
+        closest_points.start + (normal * us.radius),
+        closest_points.end,
+        normal,
+    ))
+}
+
+pub fn collide_capsule_rect(us: &Capsule, them: &Rectangle) -> Option<ContactManifold> {
+    collide_capsule_convex_polygon(us, &(*them).into())
+}
+
+pub fn collide_capsule_convex_polygon(
+    us: &Capsule,
+    them: &ConvexPolygon,
+) -> Option<ContactManifold> {
+    let line = &us.line;
+    let line_support = |direction: Vec2| {
+        if line.start.dot(direction) >= line.end.dot(direction) {
+            line.start
+        } else {
+            line.end
+        }
+    };
+
+    // Separating axis test between the inner line of the capsule and the polygon, first on the edge normals of
+    // the polygon, and then on the normal of the line itself
+    let (index, separation) = them
+        .vertices()
+        .iter()
+        .zip(them.normals())
+        .map(|(&vertex, &normal)| normal.dot(line_support(-normal) - vertex))
+        .enumerate()
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))?;
+
+    let (line_normal, line_separation) = match line.perp().try_normalize() {
+        Some(perp) => [perp, -perp]
+            .into_iter()
+            .map(|axis| {
+                let separation = them
+                    .vertices()
+                    .iter()
+                    .map(|&vertex| axis.dot(vertex - line.start))
+                    .fold(f32::INFINITY, f32::min);
+                (axis, separation)
+            })
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))?,
+        None => (Vec2::ZERO, f32::NEG_INFINITY),
+    };
+
+    if separation > 0.0 || line_separation > 0.0 {
+        // The inner line is outside the polygon, so only the rounded part of the capsule can be touching
+        let (edge, closest_points) = closest_edge_to_line(line, them.edges())?;
+
+        let diff = closest_points.as_difference();
+        if diff.length_squared() > us.radius_squared() {
+            return None;
+        }
+
+        let normal = diff.normalize();
+        if line.is_nearly_parallel_to(&edge) {
+            if let Some(manifold) = parallel_line_contact(line, us.radius, &edge, 0.0, normal) {
+                return Some(manifold);
+            }
+        }
+
+        return Some(ContactManifold::point(
+            closest_points.start + (normal * us.radius),
+            closest_points.end,
+            normal,
+        ));
+    }
+
+    // The inner line overlaps the polygon, so push out along whichever axis needs the smallest translation
+    if line_separation > separation {
+        let deepest = them.support_point(-line_normal);
+        let closest_point = line.closest_point_to_point(&deepest);
+        return Some(ContactManifold::point(
+            closest_point + (line_normal * us.radius),
+            deepest,
+            line_normal,
+        ));
+    }
+
+    let edge = them.edge(index);
+    let face_normal = them.normals()[index];
+    let normal = -face_normal;
+    let project = |point: Vec2| point - (face_normal * face_normal.dot(point - edge.start));
+
+    if line.is_nearly_parallel_to(&edge) {
+        let tangent = edge.as_difference().normalize();
+        let (edge_min, edge_max) = min_max(tangent.dot(edge.start), tangent.dot(edge.end));
+        if let Some(clipped) = clip_line_to_range(line, tangent, edge_min, edge_max) {
+            // The capsule is lying flat against the edge
+            return Some(ContactManifold::edge(
+                Line::new(
+                    clipped.start + (normal * us.radius),
+                    clipped.end + (normal * us.radius),
+                ),
+                Line::new(project(clipped.start), project(clipped.end)),
+                normal,
+            ));
+        }
+    }
+
+    let deepest = line_support(normal);
+    Some(ContactManifold::point(
+        deepest + (normal * us.radius),
+        project(deepest),
+        normal,
+    ))
+}
+
+pub fn collide_shapes(us: &dyn Shape, them: &dyn Shape) -> Option<ContactManifold> {
+    let simplex = gjk::intersect(us, them)?;
+    let fallback = simplex.clone();
+
+    match gjk::penetration(us, them, simplex) {
+        Some((us_point, them_point, normal)) => {
+            Some(ContactManifold::point(us_point, them_point, normal))
+        }
+        None => {
+            // The shapes overlap without enclosing any area (e.g. a pair of colinear lines), so they can only be
+            // touching, and any normal that is perpendicular to the overlap will do
+            let direction = match fallback.as_slice() {
+                [a, b, ..] => b.point - a.point,
+                _ => Vec2::ZERO,
+            };
+            let normal = direction.perp().try_normalize().unwrap_or(Vec2::Y);
+            Some(ContactManifold::point(
+                fallback[0].us,
+                fallback[0].us,
+                normal,
+            ))
+        }
     }
 }
 
-pub fn collide_capsule_polygon(us: &Capsule, them: &Polygon) -> Option<ContactManifold> {}
+/// Like [`collide_shapes`], but respects the concavities of `them` rather than treating it as its convex hull.
+pub fn collide_shape_polygon(us: &dyn Shape, them: &Polygon) -> Option<ContactManifold> {
+    if let Some(them) = them.as_convex() {
+        return collide_shapes(us, them);
+    }
 
-pub fn collide_capsule_rect(us: &Capsule, them: &Rectangle) -> Option<ContactManifold> {}
+    // Concave polygons are split into convex pieces to find out whether the shape overlaps them at all
+    if !them
+        .triangulate()
+        .iter()
+        .any(|triangle| gjk::intersect(us, triangle).is_some())
+    {
+        return None;
+    }
+
+    // The diagonals between the pieces aren't real surfaces, so only push the shape out through the outline.
+    // It can only pass through edges that it lies alongside, and it goes through whichever is the shallowest
+    let winding = them
+        .edges()
+        .map(|edge| edge.start.perp_dot(edge.end))
+        .sum::<f32>()
+        .signum();
+    let (us_point, outward, depth) = them
+        .edges()
+        .filter_map(|edge| {
+            let along = (edge.end - edge.start).try_normalize()?;
+            let outward = -along.perp() * winding;
+            let alongside = along.dot(us.support_point(along)) > along.dot(edge.start)
+                && along.dot(us.support_point(-along)) < along.dot(edge.end);
+            let us_point = us.support_point(-outward);
+            let depth = outward.dot(edge.start - us_point);
+            (alongside && depth > 0.0).then_some((us_point, outward, depth))
+        })
+        .min_by(|(_, _, a), (_, _, b)| a.total_cmp(b))?;
+
+    Some(ContactManifold::point(
+        us_point,
+        us_point + (outward * depth),
+        -outward,
+    ))
+}
 
 pub fn collide_circle_circle(us: &Circle, them: &Circle) -> Option<ContactManifold> {
     let diff = them.position - us.position;
     if diff.length_squared() <= (us.radius + them.radius).powi(2) {
+        // Concentric circles can be pushed apart in any direction
+        let normal = diff.try_normalize().unwrap_or(Vec2::Y);
         Some(ContactManifold::point(
-            us.position + diff.clamp_length(us.radius, us.radius),
-            them.position - diff.clamp_length(them.radius, them.radius),
-            diff.normalize(),
+            us.position + (normal * us.radius),
+            them.position - (normal * them.radius),
+            normal,
         ))
     } else {
         None
     }
 }
 
-pub fn collide_circle_line(us: &Circle, them: &Line) -> Option<ContactManifold> {}
+pub fn collide_circle_line(us: &Circle, them: &Line) -> Option<ContactManifold> {
+    collide_capsule_circle(&Capsule::new(*them, 0.0), us).map(|manifold| -manifold)
+}
 
 pub fn collide_circle_point(us: &Circle, them: &Point) -> Option<ContactManifold> {
-    if us.position.distance_squared(them.0) <= us.radius_squared() {
-        Some(ContactManifold::coincident(them.0))
+    collide_circle_circle(us, &Circle::new(them.0, 0.0))
+}
+
+pub fn collide_circle_polygon(us: &Circle, them: &Polygon) -> Option<ContactManifold> {
+    let (edge, closest_point) = them.closest_edge(us.position)?;
+
+    if them.contains(us.position) {
+        // The center of the circle is inside the polygon, so push it out through the closest edge or vertex
+        let normal = (us.position - closest_point)
+            .try_normalize()
+            .unwrap_or_else(|| -them.outward_normal(&edge));
+        Some(ContactManifold::point(
+            us.support_point(normal),
+            closest_point,
+            normal,
+        ))
     } else {
-        None
+        // The closest point is either in the interior of an edge, or on a vertex
+        let diff = closest_point - us.position;
+        if diff.length_squared() <= us.radius_squared() {
+            let normal = diff
+                .try_normalize()
+                .unwrap_or_else(|| -them.outward_normal(&edge));
+            Some(ContactManifold::point(
+                us.support_point(normal),
+                closest_point,
+                normal,
+            ))
+        } else {
+            None
+        }
     }
 }
 
-pub fn collide_circle_polygon(us: &Circle, them: &Polygon) -> Option<ContactManifold> {}
+/// The point on `line` that is furthest from the perimeter of `polygon`, out of the points that are inside it.
+///
+/// `line` must overlap `polygon`.
+fn deepest_point_in_polygon(line: &Line, polygon: &Polygon) -> Vec2 {
+    // Split the line where it crosses the edges of the polygon, and consider the ends and middle of each section
+    let mut crossings = vec![0.0, 1.0];
+    let direction = line.as_difference();
+    for edge in polygon.edges() {
+        let denominator = direction.perp_dot(edge.as_difference());
+        if denominator.abs() > f32::EPSILON && edge.intersects_line(line) {
+            crossings.push((edge.start - line.start).perp_dot(edge.as_difference()) / denominator);
+        }
+    }
+
+    crossings.sort_by(f32::total_cmp);
+
+    let candidates = crossings
+        .windows(2)
+        .flat_map(|t| [t[0], (t[0] + t[1]) / 2.0, t[1]])
+        .map(|t| line.parametric_point(t.clamp(0.0, 1.0)));
+
+    let depth = |point: Vec2| {
+        let (closest_point, inside) = polygon.closest_point_on_perimeter(point);
+        if inside {
+            closest_point.distance_squared(point)
+        } else {
+            -closest_point.distance_squared(point)
+        }
+    };
+
+    candidates
+        .max_by(|a, b| depth(*a).total_cmp(&depth(*b)))
+        .unwrap_or(line.start)
+}
+
+/// The edge that is closest to `line`, and the line between the closest points on `line` and that edge.
+///
+/// When several edges are equally close, edges that are parallel to `line` are preferred, so that lines resting
+/// flat against a corner produce an edge contact.
+fn closest_edge_to_line(line: &Line, edges: impl Iterator<Item = Line>) -> Option<(Line, Line)> {
+    edges
+        .map(|edge| {
+            let closest_points = line.closest_point_to_line(&edge);
+            let distance = closest_points.length();
+            (edge, closest_points, distance)
+        })
+        .min_by(|(a_edge, _, a_distance), (b_edge, _, b_distance)| {
+            if (a_distance - b_distance).abs() <= 1e-4 * a_distance.max(1.0) {
+                let a_parallel = line.is_nearly_parallel_to(a_edge);
+                let b_parallel = line.is_nearly_parallel_to(b_edge);
+                b_parallel.cmp(&a_parallel)
+            } else {
+                a_distance.total_cmp(b_distance)
+            }
+        })
+        .map(|(edge, closest_points, _)| (edge, closest_points))
+}
+
+/// The contact between a rounded line (like the inner line of a capsule) and a nearly parallel, possibly also
+/// rounded, edge, where `normal` points from `line` towards `edge`. Returns `None` if the lines don't overlap.
+pub(crate) fn parallel_line_contact(
+    line: &Line,
+    radius: f32,
+    edge: &Line,
+    edge_radius: f32,
+    normal: Vec2,
+) -> Option<ContactManifold> {
+    let tangent = edge.as_difference().normalize();
+    let (edge_min, edge_max) = min_max(tangent.dot(edge.start), tangent.dot(edge.end));
+    let (line_min, line_max) = min_max(tangent.dot(line.start), tangent.dot(line.end));
+
+    let us = clip_line_to_range(line, tangent, edge_min, edge_max)?;
+    let them = clip_line_to_range(edge, tangent, line_min, line_max)?;
+
+    Some(ContactManifold::edge(
+        Line::new(us.start + (normal * radius), us.end + (normal * radius)),
+        Line::new(
+            them.start - (normal * edge_radius),
+            them.end - (normal * edge_radius),
+        ),
+        normal,
+    ))
+}
+
+fn min_max(a: f32, b: f32) -> (f32, f32) {
+    if a <= b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
 
 pub fn collide_circle_rect(us: &Circle, them: &Rectangle) -> Option<ContactManifold> {
     let (closest_point, center_is_in_rect) = them.closest_point_on_perimeter(us.position);
+
+    // When the center of the circle lies on the perimeter, push it out through the nearest face
+    let face_normal = || {
+        let polygon = ConvexPolygon::from(*them);
+        -polygon.normals()[polygon.max_separation(us.position).0]
+    };
+
     if center_is_in_rect {
-        let normal = (us.position - closest_point).normalize();
+        let normal = (us.position - closest_point)
+            .try_normalize()
+            .unwrap_or_else(face_normal);
         Some(ContactManifold::point(
             us.support_point(normal),
             closest_point,
             normal,
         ))
     } else if us.contains(closest_point) {
-        let normal = (closest_point - us.position).normalize();
+        let normal = (closest_point - us.position)
+            .try_normalize()
+            .unwrap_or_else(face_normal);
         Some(ContactManifold::point(
             us.support_point(normal),
             closest_point,
@@ -126,49 +442,273 @@ pub fn collide_circle_rect(us: &Circle, them: &Rectangle) -> Option<ContactManif
     }
 }
 
+pub fn collide_circle_convex_polygon(us: &Circle, them: &ConvexPolygon) -> Option<ContactManifold> {
+    let (index, separation) = them.max_separation(us.position);
+    if separation > us.radius {
+        return None;
+    }
+
+    if separation <= 0.0 {
+        // The center of the circle is inside the polygon, so push it out through the nearest edge
+        let normal = -them.normals()[index];
+        Some(ContactManifold::point(
+            us.support_point(normal),
+            us.position + (normal * separation),
+            normal,
+        ))
+    } else {
+        // The center of the circle is outside the polygon, so the closest point may be on an edge or a vertex
+        let closest_point = them
+            .edges()
+            .map(|edge| edge.closest_point_to_point(&us.position))
+            .min_by(|a, b| {
+                a.distance_squared(us.position)
+                    .total_cmp(&b.distance_squared(us.position))
+            })?;
+
+        let diff = closest_point - us.position;
+        if diff.length_squared() <= us.radius_squared() {
+            let normal = diff.normalize();
+            Some(ContactManifold::point(
+                us.support_point(normal),
+                closest_point,
+                normal,
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+pub fn collide_convex_polygon_convex_polygon(
+    us: &ConvexPolygon,
+    them: &ConvexPolygon,
+) -> Option<ContactManifold> {
+    // Cheap early-out using the bounding circles
+    let radii = us.bounding_radius() + them.bounding_radius();
+    if us.centroid().distance_squared(them.centroid()) > radii * radii {
+        return None;
+    }
+
+    // Negative separations represent penetration, so any positive separation is a separating axis
+    let (us_index, us_separation) = max_polygon_separation(us, them);
+    if us_separation > 0.0 {
+        return None;
+    }
+
+    let (them_index, them_separation) = max_polygon_separation(them, us);
+    if them_separation > 0.0 {
+        return None;
+    }
+
+    // Prefer the edges of `us` as the reference face, so that the manifold doesn't flip-flop between frames
+    // when both axes are roughly equally good
+    if them_separation > (0.98 * us_separation) + 0.001 {
+        polygon_face_contact(them, us, them_index).map(|manifold| -manifold)
+    } else {
+        polygon_face_contact(us, them, us_index)
+    }
+}
+
+/// The index of the edge of `reference` that `incident` is furthest outside of, along with that separation.
+fn max_polygon_separation(reference: &ConvexPolygon, incident: &ConvexPolygon) -> (usize, f32) {
+    reference
+        .vertices()
+        .iter()
+        .zip(reference.normals())
+        .map(|(&vertex, &normal)| normal.dot(incident.support_point(-normal) - vertex))
+        .enumerate()
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .unwrap_or((0, f32::INFINITY))
+}
+
+/// Computes the contact between the given edge of `reference` and the edge of `incident` facing it.
+fn polygon_face_contact(
+    reference: &ConvexPolygon,
+    incident: &ConvexPolygon,
+    index: usize,
+) -> Option<ContactManifold> {
+    let normal = reference.normals()[index];
+    let reference_edge = reference.edge(index);
+
+    // The incident edge is the one whose normal is most anti-parallel to the reference normal
+    let (incident_index, _) = incident
+        .normals()
+        .iter()
+        .map(|incident_normal| normal.dot(*incident_normal))
+        .enumerate()
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))?;
+    let incident_edge = incident.edge(incident_index);
+
+    // Clip the incident edge to the sides of the reference edge
+    let tangent = reference_edge.as_difference().normalize();
+    let clipped = clip_line_to_range(
+        &incident_edge,
+        tangent,
+        tangent.dot(reference_edge.start),
+        tangent.dot(reference_edge.end),
+    )
+    .unwrap_or_else(|| {
+        let deepest = incident.support_point(-normal);
+        Line::new(deepest, deepest)
+    });
+
+    let depth = |point: Vec2| normal.dot(reference_edge.start - point);
+    let projected = |point: Vec2| point + (normal * depth(point));
+
+    if depth(clipped.start) >= 0.0
+        && depth(clipped.end) >= 0.0
+        && incident_edge.is_nearly_parallel_to(&reference_edge)
+    {
+        // The edges are flush against each other
+        Some(ContactManifold::edge(
+            Line::new(projected(clipped.start), projected(clipped.end)),
+            clipped,
+            normal,
+        ))
+    } else {
+        let deepest = if depth(clipped.start) >= depth(clipped.end) {
+            clipped.start
+        } else {
+            clipped.end
+        };
+
+        Some(ContactManifold::point(projected(deepest), deepest, normal))
+    }
+}
+
+/// Clips `line` to the part of it whose projection onto `axis` lies between `min` and `max`.
+fn clip_line_to_range(line: &Line, axis: Vec2, min: f32, max: f32) -> Option<Line> {
+    let start = axis.dot(line.start);
+    let end = axis.dot(line.end);
+
+    if (start < min && end < min) || (start > max && end > max) {
+        return None;
+    }
+
+    let clip = |t: f32| {
+        if (end - start).abs() <= f32::EPSILON {
+            line.start
+        } else {
+            line.parametric_point((t - start) / (end - start))
+        }
+    };
+
+    Some(Line::new(
+        clip(start.clamp(min, max)),
+        clip(end.clamp(min, max)),
+    ))
+}
+
 pub fn collide_line_line(us: &Line, them: &Line) -> Option<ContactManifold> {
     match us.intersect_line(them) {
         LineIntersection::Disjoint => None,
-        LineIntersection::Intersecting(point) => Some(ContactManifold::Coincident(point)),
+        LineIntersection::Intersecting(_) => collide_shapes(us, them),
         LineIntersection::Colinear(line) => {
-            Some(ContactManifold::Coincident(line.parametric_point(0.5)))
+            // Overlapping colinear lines are only touching, so they can be pushed apart to either side
+            let normal = us.perp().try_normalize().unwrap_or(Vec2::Y);
+            Some(ContactManifold::edge(line, line, normal))
         }
     }
 }
 
 pub fn collide_line_point(us: &Line, them: &Point) -> Option<ContactManifold> {
     if us.distance_to_point_squared(&them.0) <= f32::EPSILON * f32::EPSILON {
-        Some(ContactManifold::Coincident(them.0))
+        let normal = us.perp().try_normalize().unwrap_or(Vec2::Y);
+        Some(ContactManifold::point(them.0, them.0, normal))
     } else {
         None
     }
 }
 
-pub fn collide_line_polygon(us: &Line, them: &Polygon) -> Option<ContactManifold> {}
+pub fn collide_line_polygon(us: &Line, them: &Polygon) -> Option<ContactManifold> {
+    collide_capsule_polygon(&Capsule::new(*us, 0.0), them)
+}
 
-pub fn collide_line_rect(us: &Line, them: &Rectangle) -> Option<ContactManifold> {}
+pub fn collide_line_rect(us: &Line, them: &Rectangle) -> Option<ContactManifold> {
+    collide_shapes(us, them)
+}
+
+pub fn collide_point_convex_polygon(us: &Point, them: &ConvexPolygon) -> Option<ContactManifold> {
+    let (index, separation) = them.max_separation(us.0);
+    if separation > 0.0 {
+        return None;
+    }
+
+    // Push the point out through the edge that it is closest to
+    let outward = them.normals()[index];
+    Some(ContactManifold::point(
+        us.0,
+        us.0 - (outward * separation),
+        -outward,
+    ))
+}
 
 pub fn collide_point_point(us: &Point, them: &Point) -> Option<ContactManifold> {
-    if us.distance_squared(them.0) <= f32::EPSILON * f32::EPSILON {
-        Some(ContactManifold::Coincident(us.0))
+    let diff = them.0 - us.0;
+    if diff.length_squared() <= f32::EPSILON * f32::EPSILON {
+        let normal = diff.try_normalize().unwrap_or(Vec2::Y);
+        Some(ContactManifold::point(us.0, them.0, normal))
     } else {
         None
     }
 }
 
-pub fn collide_point_polygon(us: &Point, them: &Polygon) -> Option<ContactManifold> {}
+pub fn collide_point_polygon(us: &Point, them: &Polygon) -> Option<ContactManifold> {
+    collide_circle_polygon(&Circle::new(us.0, 0.0), them)
+}
 
 pub fn collide_point_rect(us: &Point, them: &Rectangle) -> Option<ContactManifold> {
-    if them.contains(us.0) {
-        Some(ContactManifold::coincident(us.0))
-    } else {
-        None
+    collide_point_convex_polygon(us, &(*them).into())
+}
+
+pub fn collide_polygon_polygon(us: &Polygon, them: &Polygon) -> Option<ContactManifold> {
+    if let Some(us) = us.as_convex() {
+        return collide_convex_polygon_polygon(us, them);
+    }
+
+    if let Some(them) = them.as_convex() {
+        return collide_convex_polygon_polygon(them, us).map(|manifold| -manifold);
     }
+
+    // Concave polygons have no single minimum translation, so push apart the edge that is deepest inside the
+    // other polygon instead
+    let us_edges = us
+        .edges()
+        .filter_map(|edge| collide_line_polygon(&edge, them));
+    let them_edges = them
+        .edges()
+        .filter_map(|edge| collide_line_polygon(&edge, us).map(|manifold| -manifold));
+
+    us_edges
+        .chain(them_edges)
+        .max_by(|a, b| a.depth().total_cmp(&b.depth()))
 }
 
-pub fn collide_polygon_polygon(us: &Polygon, them: &Polygon) -> Option<ContactManifold> {}
+pub fn collide_convex_polygon_polygon(
+    us: &ConvexPolygon,
+    them: &Polygon,
+) -> Option<ContactManifold> {
+    if let Some(them) = them.as_convex() {
+        return collide_convex_polygon_convex_polygon(us, them);
+    }
+
+    // As with a pair of concave polygons, push apart the edge that is deepest inside the other polygon
+    let us_edges = us
+        .edges()
+        .filter_map(|edge| collide_line_polygon(&edge, them));
+    let them_edges = them.edges().filter_map(|edge| {
+        collide_capsule_convex_polygon(&Capsule::new(edge, 0.0), us).map(|manifold| -manifold)
+    });
 
-pub fn collide_polygon_rect(us: &Polygon, them: &Rectangle) -> Option<ContactManifold> {}
+    us_edges
+        .chain(them_edges)
+        .max_by(|a, b| a.depth().total_cmp(&b.depth()))
+}
+
+pub fn collide_polygon_rect(us: &Polygon, them: &Rectangle) -> Option<ContactManifold> {
+    collide_convex_polygon_polygon(&(*them).into(), us).map(|manifold| -manifold)
+}
 
 pub fn collide_rect_rect(us: &Rectangle, them: &Rectangle) -> Option<ContactManifold> {
     // The penetration depths of each side of the rects
@@ -214,3 +754,45 @@ pub fn collide_rect_rect(us: &Rectangle, them: &Rectangle) -> Option<ContactMani
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parallel_capsules_touch_along_their_overlap() {
+        let us = Capsule::new(Line::new(Vec2::new(10.0, 0.0), Vec2::new(14.0, 0.0)), 1.0);
+        let them = Capsule::new(Line::new(Vec2::new(11.0, 1.5), Vec2::new(16.0, 1.5)), 1.0);
+
+        let manifold = collide_capsule_capsule(&us, &them).unwrap();
+        assert!(matches!(manifold, ContactManifold::Edge(_)));
+        assert!((manifold.depth() - 0.5).abs() < 1e-4);
+        assert!(manifold.normal().abs_diff_eq(Vec2::Y, 1e-4));
+
+        for contact in manifold.contact_points() {
+            assert!((11.0 - 1e-4..=14.0 + 1e-4).contains(&contact.us.x));
+            assert!((11.0 - 1e-4..=14.0 + 1e-4).contains(&contact.them.x));
+            assert!((contact.us.y - 1.0).abs() < 1e-4);
+            assert!((contact.them.y - 0.5).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn circle_centered_on_rect_edge() {
+        let us = Circle::new(Vec2::new(1.0, 2.0), 0.5);
+        let them = Rectangle::new(0.0, 0.0, 4.0, 2.0);
+
+        let manifold = collide_circle_rect(&us, &them).unwrap();
+        assert!(manifold.normal().abs_diff_eq(Vec2::NEG_Y, 1e-4));
+        assert!((manifold.depth() - 0.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn crossing_capsules_are_pushed_apart() {
+        let us = Capsule::new(Line::new(Vec2::new(-2.0, 0.0), Vec2::new(2.0, 0.0)), 0.5);
+        let them = Capsule::new(Line::new(Vec2::new(0.0, -2.0), Vec2::new(0.0, 2.0)), 0.5);
+
+        let manifold = collide_capsule_capsule(&us, &them).unwrap();
+        assert!(manifold.depth() > 0.0);
+    }
+}
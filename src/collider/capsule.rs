@@ -33,6 +33,68 @@ impl Transformable for Capsule {
     }
 }
 
+impl Shape for Capsule {
+    fn support_point(&self, direction: Vec2) -> Vec2 {
+        let end = if self.line.start.dot(direction) >= self.line.end.dot(direction) {
+            self.line.start
+        } else {
+            self.line.end
+        };
+
+        end + (direction.normalize_or_zero() * self.radius)
+    }
+
+    fn aabb(&self) -> Rectangle {
+        Rectangle::from_corners(self.line.start, self.line.end).inset(self.radius)
+    }
+
+    fn contains(&self, point: Vec2) -> bool {
+        self.line.distance_to_point_squared(&point) <= self.radius_squared()
+    }
+
+    fn feature_at(&self, point: Vec2) -> FeatureId {
+        self.line
+            .feature_at(self.line.closest_point_to_point(&point))
+    }
+
+    fn cast_ray(&self, ray: &Ray2d, max_toi: f32, solid: bool) -> Option<RayHit> {
+        if solid && self.contains(ray.origin) {
+            return Some(RayHit::inside(ray));
+        }
+
+        let axis = self.line.as_difference();
+        let offset = axis.perp().normalize_or_zero() * self.radius;
+
+        // The outline is made up of the two sides, and the outer halves of the circles around each end
+        let sides = [offset, -offset].map(|offset| {
+            let side = Line::new(self.line.start + offset, self.line.end + offset);
+            ray::line_toi(ray, &side).map(|toi| (toi, offset))
+        });
+
+        let caps = [(self.line.start, -1.0), (self.line.end, 1.0)].map(|(end, outwards)| {
+            let (enter, exit) = ray::circle_tois(ray, end, self.radius)?;
+            [enter, exit]
+                .into_iter()
+                .filter(|&toi| toi >= 0.0)
+                .map(|toi| (toi, ray.point_at(toi) - end))
+                .find(|(_, normal)| normal.dot(axis) * outwards >= 0.0)
+        });
+
+        sides
+            .into_iter()
+            .chain(caps)
+            .flatten()
+            .filter(|(toi, _)| *toi <= max_toi)
+            .min_by(|(a, _), (b, _)| a.total_cmp(b))
+            .map(|(toi, normal)| RayHit::new(ray, toi, normal))
+    }
+
+    #[cfg(feature = "debug-draw")]
+    fn debug_geometry(&self, b: &mut Builder) {
+        self.add_geometry(b);
+    }
+}
+
 impl Collides<Capsule> for Capsule {
     fn collide(&self, other: &Capsule) -> Option<ContactManifold> {
         algorithms::collide_capsule_capsule(self, other)
@@ -45,9 +107,15 @@ impl Collides<Circle> for Capsule {
     }
 }
 
+impl Collides<ConvexPolygon> for Capsule {
+    fn collide(&self, other: &ConvexPolygon) -> Option<ContactManifold> {
+        algorithms::collide_capsule_convex_polygon(self, other)
+    }
+}
+
 impl Collides<Line> for Capsule {
     fn collide(&self, other: &Line) -> Option<ContactManifold> {
-        (self.line.closest_point_to_line(other) <= self.radius_squared()).into()
+        algorithms::collide_capsule_line(self, other)
     }
 }
 
@@ -59,19 +127,13 @@ impl Collides<Point> for Capsule {
 
 impl Collides<Polygon> for Capsule {
     fn collide(&self, other: &Polygon) -> Option<ContactManifold> {
-        other.collide(self)
+        algorithms::collide_capsule_polygon(self, other)
     }
 }
 
 impl Collides<Rectangle> for Capsule {
     fn collide(&self, other: &Rectangle) -> Option<ContactManifold> {
-        (other.contains(self.line.start)
-            || other.contains(self.line.end)
-            || self.collide(&other.left()).colliding
-            || self.collide(&other.top()).colliding
-            || self.collide(&other.right()).colliding
-            || self.collide(&other.bottom()).colliding)
-            .into()
+        algorithms::collide_capsule_rect(self, other)
     }
 }
 
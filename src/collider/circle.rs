@@ -53,9 +53,49 @@ impl Transformable for Circle {
     }
 }
 
+impl Shape for Circle {
+    fn support_point(&self, direction: Vec2) -> Vec2 {
+        self.position + (direction.normalize_or_zero() * self.radius)
+    }
+
+    fn aabb(&self) -> Rectangle {
+        Rectangle::from_center_half_size(self.position, Vec2::splat(self.radius))
+    }
+
+    fn contains(&self, point: Vec2) -> bool {
+        Circle::contains(self, point)
+    }
+
+    fn cast_ray(&self, ray: &Ray2d, max_toi: f32, solid: bool) -> Option<RayHit> {
+        let (enter, exit) = ray::circle_tois(ray, self.position, self.radius)?;
+        let toi = if enter >= 0.0 {
+            enter
+        } else if exit >= 0.0 {
+            // The ray starts inside `self`
+            if solid {
+                return Some(RayHit::inside(ray));
+            }
+            exit
+        } else {
+            return None;
+        };
+
+        (toi <= max_toi).then(|| RayHit::new(ray, toi, ray.point_at(toi) - self.position))
+    }
+
+    fn feature_at(&self, _point: Vec2) -> FeatureId {
+        FeatureId::Vertex(0)
+    }
+
+    #[cfg(feature = "debug-draw")]
+    fn debug_geometry(&self, b: &mut Builder) {
+        self.add_geometry(b);
+    }
+}
+
 impl Collides<Capsule> for Circle {
     fn collide(&self, other: &Capsule) -> Option<ContactManifold> {
-        algorithms::collide_capsule_circle(other, self).neg()
+        algorithms::collide_capsule_circle(other, self).map(|manifold| -manifold)
     }
 }
 
@@ -65,9 +105,15 @@ impl Collides<Circle> for Circle {
     }
 }
 
+impl Collides<ConvexPolygon> for Circle {
+    fn collide(&self, other: &ConvexPolygon) -> Option<ContactManifold> {
+        algorithms::collide_circle_convex_polygon(self, other)
+    }
+}
+
 impl Collides<Line> for Circle {
     fn collide(&self, other: &Line) -> Option<ContactManifold> {
-        (other.distance_to_point_squared(&self.position) <= self.radius_squared()).into()
+        algorithms::collide_circle_line(self, other)
     }
 }
 
@@ -79,7 +125,7 @@ impl Collides<Point> for Circle {
 
 impl Collides<Polygon> for Circle {
     fn collide(&self, other: &Polygon) -> Option<ContactManifold> {
-        other.collide(self)
+        algorithms::collide_circle_polygon(self, other)
     }
 }
 
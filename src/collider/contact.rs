@@ -1,11 +1,14 @@
 use super::*;
 use std::ops::Neg;
 
+/// How a pair of colliding shapes are touching.
+///
+/// Every manifold has a normal pointing from `us` towards `them`, and a depth along that normal, so overlaps
+/// can always be resolved by moving `us` by [`ContactManifold::minimum_translation_vector`].
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub enum ContactManifold {
     Point(ContactPoint),
     Edge(ContactEdge),
-    Coincident(Vec2),
 }
 
 impl ContactManifold {
@@ -17,8 +20,59 @@ impl ContactManifold {
         Self::Edge(ContactEdge::new(us, them, normal))
     }
 
-    pub fn coincident(point: Vec2) -> Self {
-        Self::Coincident(point)
+    /// The unit normal of the contact, pointing from `us` towards `them`.
+    pub fn normal(&self) -> Vec2 {
+        match self {
+            Self::Point(point) => point.normal,
+            Self::Edge(edge) => edge.normal,
+        }
+    }
+
+    /// How far the shapes overlap along [`ContactManifold::normal`].
+    ///
+    /// This is positive when the shapes are overlapping and 0 when they are only just touching. Speculative
+    /// contacts between shapes that are within a [`ContactSkin`] of each other have a negative depth, the
+    /// negation of the gap between them.
+    pub fn depth(&self) -> f32 {
+        match self {
+            Self::Point(point) => point.depth(),
+            Self::Edge(edge) => edge.depth(),
+        }
+    }
+
+    /// The individual points of contact, with one point for a [`ContactPoint`] and two for a [`ContactEdge`].
+    pub fn contact_points(&self) -> impl Iterator<Item = ContactPoint> {
+        let points = match self {
+            Self::Point(point) => [Some(*point), None],
+            Self::Edge(edge) => {
+                let [start, end] = edge.contact_points();
+                [Some(start), Some(end)]
+            }
+        };
+
+        points.into_iter().flatten()
+    }
+
+    /// The smallest translation of `us` that separates it from `them`.
+    pub fn minimum_translation_vector(&self) -> Vec2 {
+        -self.normal() * self.depth()
+    }
+
+    /// Fills in the feature IDs of the contact points, by looking up which features of `us` and `them` they lie on.
+    ///
+    /// IDs only depend on the shapes themselves, so the same contact keeps the same IDs from one frame to the next.
+    pub fn with_features(self, us: &dyn Shape, them: &dyn Shape) -> Self {
+        let id = |us_point: Vec2, them_point: Vec2| {
+            ContactId::new(us.feature_at(us_point), them.feature_at(them_point))
+        };
+
+        match self {
+            Self::Point(point) => Self::Point(point.with_id(id(point.us, point.them))),
+            Self::Edge(edge) => {
+                let [start, end] = edge.contact_points();
+                Self::Edge(edge.with_ids([id(start.us, start.them), id(end.us, end.them)]))
+            }
+        }
     }
 
     pub fn negate(&mut self) {
@@ -29,11 +83,10 @@ impl ContactManifold {
 impl Neg for ContactManifold {
     type Output = Self;
 
-    fn neg(mut self) -> Self::Output {
+    fn neg(self) -> Self::Output {
         match self {
             Self::Point(point) => Self::Point(point.neg()),
             Self::Edge(edge) => Self::Edge(edge.neg()),
-            Self::Coincident(point) => Self::Coincident(point),
         }
     }
 }
@@ -50,9 +103,45 @@ impl From<ContactEdge> for ContactManifold {
     }
 }
 
-impl From<Vec2> for ContactManifold {
-    fn from(point: Vec2) -> Self {
-        Self::Coincident(point)
+/// A part of the outline of a shape that can produce a contact.
+///
+/// Vertices and edges are numbered in the order that the shape defines them, where the `i`th edge runs from the
+/// `i`th vertex to the next. Round shapes count as a single vertex with a radius, and capsules have a vertex at
+/// each end of their inner line with edge 0 between them.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, Debug)]
+pub enum FeatureId {
+    Vertex(u32),
+    Edge(u32),
+    /// The feature couldn't be identified, e.g. because the shape is a [`Shape`] that doesn't support it.
+    #[default]
+    Unknown,
+}
+
+/// Identifies a contact by the features of each shape that produced it, which makes it possible to track the
+/// same contact across frames.
+///
+/// IDs are only filled in when colliding a pair of [`Collider`]s. Manifolds from the individual shapes'
+/// [`Collides`] impls have [`FeatureId::Unknown`] IDs until passed through [`ContactManifold::with_features`].
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, Debug)]
+pub struct ContactId {
+    pub us: FeatureId,
+    pub them: FeatureId,
+}
+
+impl ContactId {
+    pub fn new(us: FeatureId, them: FeatureId) -> Self {
+        Self { us, them }
+    }
+}
+
+impl Neg for ContactId {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            us: self.them,
+            them: self.us,
+        }
     }
 }
 
@@ -61,22 +150,39 @@ pub struct ContactPoint {
     pub us: Vec2,
     pub them: Vec2,
     pub normal: Vec2,
+    pub id: ContactId,
 }
 
 impl ContactPoint {
     pub fn new(us: Vec2, them: Vec2, normal: Vec2) -> Self {
         debug_assert!(normal.is_normalized());
-        Self { us, them, normal }
+        Self {
+            us,
+            them,
+            normal,
+            id: ContactId::default(),
+        }
+    }
+
+    pub fn with_id(mut self, id: ContactId) -> Self {
+        self.id = id;
+        self
     }
 
     pub fn separation_vector(&self) -> Vec2 {
         self.them - self.us
     }
 
+    /// The distance between the contact points along the normal, which is negative when the shapes overlap.
     pub fn penetration_depth(&self) -> f32 {
         self.separation_vector().dot(self.normal)
     }
 
+    /// How far the shapes overlap along the normal, see [`ContactManifold::depth`].
+    pub fn depth(&self) -> f32 {
+        -self.penetration_depth()
+    }
+
     pub fn negate(&mut self) {
         *self = self.neg();
     }
@@ -90,6 +196,7 @@ impl Neg for ContactPoint {
             us: self.them,
             them: self.us,
             normal: -self.normal,
+            id: -self.id,
         }
     }
 }
@@ -97,15 +204,49 @@ impl Neg for ContactPoint {
 #[derive(Clone, Copy, Default, PartialEq, Debug)]
 pub struct ContactEdge {
     pub us: Line,
+    /// The matching line on `them`, which always runs in the same direction as `us`.
     pub them: Line,
     pub normal: Vec2,
+    /// The IDs of the contacts at the start and end of the lines.
+    pub ids: [ContactId; 2],
 }
 
 impl ContactEdge {
     pub fn new(us: Line, them: Line, normal: Vec2) -> Self {
         debug_assert!(normal.is_normalized());
-        debug_assert!(us.is_parallel_to(&them));
-        Self { us, them, normal }
+        debug_assert!(us.is_nearly_parallel_to(&them));
+
+        let them = if us.as_difference().dot(them.as_difference()) < 0.0 {
+            Line::new(them.end, them.start)
+        } else {
+            them
+        };
+
+        Self {
+            us,
+            them,
+            normal,
+            ids: [ContactId::default(); 2],
+        }
+    }
+
+    pub fn with_ids(mut self, ids: [ContactId; 2]) -> Self {
+        self.ids = ids;
+        self
+    }
+
+    /// How far the shapes overlap along the normal, see [`ContactManifold::depth`].
+    pub fn depth(&self) -> f32 {
+        (self.us.start - self.them.start).dot(self.normal)
+    }
+
+    /// Contact points at each end of `us`, paired with the matching points on `them`.
+    pub fn contact_points(&self) -> [ContactPoint; 2] {
+        let [start, end] = self.ids;
+        [(self.us.start, start), (self.us.end, end)].map(|(us, id)| {
+            let them = us + (self.normal * (self.them.start - us).dot(self.normal));
+            ContactPoint::new(us, them, self.normal).with_id(id)
+        })
     }
 
     pub fn negate(&mut self) {
@@ -121,6 +262,7 @@ impl Neg for ContactEdge {
             us: self.them,
             them: self.us,
             normal: -self.normal,
+            ids: self.ids.map(|id| -id),
         }
     }
 }
@@ -0,0 +1,334 @@
+use super::*;
+use crate::transform_ext::TransformPoint2;
+
+/// A convex polygon.
+///
+/// Unlike [`Polygon`], convexity is validated once on construction, and the edge normals, centroid and bounding
+/// radius are computed up-front, which makes collision tests against convex polygons considerably cheaper.
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct ConvexPolygon {
+    vertices: Vec<Vec2>,
+    normals: Vec<Vec2>,
+    centroid: Vec2,
+    bounding_radius: f32,
+}
+
+impl ConvexPolygon {
+    /// Creates a convex polygon from the given vertices, in either winding order.
+    ///
+    /// Returns `None` if the vertices don't form a convex polygon with a non-zero area. Colinear and duplicate
+    /// vertices are removed.
+    pub fn new(vertices: Vec<Vec2>) -> Option<Self> {
+        let mut vertices = remove_colinear(vertices);
+        if vertices.len() < 3 {
+            return None;
+        }
+
+        let signed_area: f32 = polygon::EdgesIterator::new(&vertices)
+            .map(|edge| edge.start.perp_dot(edge.end))
+            .sum();
+
+        if signed_area < 0.0 {
+            vertices.reverse();
+        }
+
+        // Every corner must turn anticlockwise, and the edges must only wind around once
+        let len = vertices.len();
+        let mut total_angle = 0.0;
+        for i in 0..len {
+            let before = vertices[i] - vertices[(i + len - 1) % len];
+            let after = vertices[(i + 1) % len] - vertices[i];
+            let turn = before.perp_dot(after);
+            if turn <= 0.0 {
+                return None;
+            }
+            total_angle += before.angle_between(after);
+        }
+
+        if (total_angle - std::f32::consts::TAU).abs() > 1e-3 {
+            return None;
+        }
+
+        Some(Self::from_vertices_unchecked(vertices))
+    }
+
+    /// Computes the convex hull of the given points, using the monotone chain algorithm.
+    ///
+    /// Returns `None` if all of the points are colinear.
+    pub fn convex_hull(points: &[Vec2]) -> Option<Self> {
+        let mut points = points.to_vec();
+        points.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+        points.dedup();
+
+        if points.len() < 3 {
+            return None;
+        }
+
+        let mut hull: Vec<Vec2> = Vec::with_capacity(points.len() * 2);
+        for pass in [
+            points.as_slice(),
+            &points.iter().rev().copied().collect::<Vec<_>>(),
+        ] {
+            let start = hull.len();
+            for &point in pass {
+                while hull.len() >= start + 2 {
+                    let a = hull[hull.len() - 2];
+                    let b = hull[hull.len() - 1];
+                    if (b - a).perp_dot(point - a) > 0.0 {
+                        break;
+                    }
+                    hull.pop();
+                }
+                hull.push(point);
+            }
+            // The last point of each chain is the first point of the other
+            hull.pop();
+        }
+
+        Self::new(hull)
+    }
+
+    /// Builds a convex polygon from anticlockwise vertices that are already known to be convex.
+    fn from_vertices_unchecked(vertices: Vec<Vec2>) -> Self {
+        let normals = polygon::EdgesIterator::new(&vertices)
+            .map(|edge| -edge.perp().normalize())
+            .collect();
+
+        let centroid = centroid(&vertices);
+        let bounding_radius = bounding_radius(&vertices, centroid);
+
+        Self {
+            vertices,
+            normals,
+            centroid,
+            bounding_radius,
+        }
+    }
+
+    /// The vertices of `self`, in anticlockwise order (unless `self` has been transformed by a reflection).
+    pub fn vertices(&self) -> &[Vec2] {
+        &self.vertices
+    }
+
+    /// The outward-facing unit normals of each edge of `self`, where the `i`th normal belongs to the edge from
+    /// the `i`th vertex to the next.
+    pub fn normals(&self) -> &[Vec2] {
+        &self.normals
+    }
+
+    pub fn centroid(&self) -> Vec2 {
+        self.centroid
+    }
+
+    /// The distance from the centroid to the furthest vertex.
+    pub fn bounding_radius(&self) -> f32 {
+        self.bounding_radius
+    }
+
+    pub fn bounding_circle(&self) -> Circle {
+        Circle::new(self.centroid, self.bounding_radius)
+    }
+
+    pub fn edges(&self) -> polygon::EdgesIterator<'_> {
+        polygon::EdgesIterator::new(&self.vertices)
+    }
+
+    /// The `i`th edge of `self`, from the `i`th vertex to the next.
+    pub fn edge(&self, index: usize) -> Line {
+        Line::new(
+            self.vertices[index],
+            self.vertices[(index + 1) % self.vertices.len()],
+        )
+    }
+
+    pub fn contains(&self, point: Vec2) -> bool {
+        self.vertices
+            .iter()
+            .zip(&self.normals)
+            .all(|(&vertex, normal)| normal.dot(point - vertex) <= 0.0)
+    }
+
+    /// The index and signed distance of the edge that `point` is furthest outside of.
+    ///
+    /// The distance is negative when `point` lies inside `self`.
+    pub fn max_separation(&self, point: Vec2) -> (usize, f32) {
+        self.vertices
+            .iter()
+            .zip(&self.normals)
+            .map(|(&vertex, normal)| normal.dot(point - vertex))
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .unwrap_or((0, f32::INFINITY))
+    }
+}
+
+impl From<Rectangle> for ConvexPolygon {
+    fn from(rect: Rectangle) -> Self {
+        Self {
+            vertices: vec![
+                rect.min(),
+                rect.bottom_right_corner(),
+                rect.max(),
+                rect.top_left_corner(),
+            ],
+            normals: vec![Vec2::NEG_Y, Vec2::X, Vec2::Y, Vec2::NEG_X],
+            centroid: rect.center(),
+            bounding_radius: rect.half_size().length(),
+        }
+    }
+}
+
+impl Transformable for ConvexPolygon {
+    fn to_transformed(&self, transform: &GlobalTransform) -> Self {
+        let vertices: Vec<Vec2> = self
+            .vertices
+            .iter()
+            .map(|&vertex| transform.transform_point2(vertex))
+            .collect();
+
+        let normals = self
+            .normals
+            .iter()
+            .map(|&normal| transform.transform_normal2(normal))
+            .collect();
+
+        let centroid = transform.transform_point2(self.centroid);
+        let bounding_radius = bounding_radius(&vertices, centroid);
+
+        Self {
+            vertices,
+            normals,
+            centroid,
+            bounding_radius,
+        }
+    }
+}
+
+impl Shape for ConvexPolygon {
+    fn support_point(&self, direction: Vec2) -> Vec2 {
+        self.vertices
+            .iter()
+            .copied()
+            .max_by(|a, b| a.dot(direction).total_cmp(&b.dot(direction)))
+            .unwrap_or_default()
+    }
+
+    fn contains(&self, point: Vec2) -> bool {
+        ConvexPolygon::contains(self, point)
+    }
+
+    fn feature_at(&self, point: Vec2) -> FeatureId {
+        polygon::outline_feature(&self.vertices, point)
+    }
+
+    fn cast_ray(&self, ray: &Ray2d, max_toi: f32, solid: bool) -> Option<RayHit> {
+        ray::cast_ray_edges(ray, self.edges(), max_toi, solid, self.contains(ray.origin))
+    }
+
+    #[cfg(feature = "debug-draw")]
+    fn debug_geometry(&self, b: &mut Builder) {
+        self.add_geometry(b);
+    }
+}
+
+impl Collides<Capsule> for ConvexPolygon {
+    fn collide(&self, other: &Capsule) -> Option<ContactManifold> {
+        algorithms::collide_capsule_convex_polygon(other, self).map(|manifold| -manifold)
+    }
+}
+
+impl Collides<Circle> for ConvexPolygon {
+    fn collide(&self, other: &Circle) -> Option<ContactManifold> {
+        algorithms::collide_circle_convex_polygon(other, self).map(|manifold| -manifold)
+    }
+}
+
+impl Collides<ConvexPolygon> for ConvexPolygon {
+    fn collide(&self, other: &ConvexPolygon) -> Option<ContactManifold> {
+        algorithms::collide_convex_polygon_convex_polygon(self, other)
+    }
+}
+
+impl Collides<Point> for ConvexPolygon {
+    fn collide(&self, other: &Point) -> Option<ContactManifold> {
+        algorithms::collide_point_convex_polygon(other, self).map(|manifold| -manifold)
+    }
+}
+
+impl Collides<Polygon> for ConvexPolygon {
+    fn collide(&self, other: &Polygon) -> Option<ContactManifold> {
+        algorithms::collide_convex_polygon_polygon(self, other)
+    }
+}
+
+impl Collides<Rectangle> for ConvexPolygon {
+    fn collide(&self, other: &Rectangle) -> Option<ContactManifold> {
+        algorithms::collide_convex_polygon_convex_polygon(self, &(*other).into())
+    }
+}
+
+#[cfg(feature = "debug-draw")]
+impl Geometry for ConvexPolygon {
+    fn add_geometry(&self, b: &mut Builder) {
+        let mut iter = self.vertices.iter();
+
+        let Some(first) = iter.next() else { return; };
+
+        b.begin((first.x, first.y).into());
+
+        for vertex in iter {
+            b.line_to((vertex.x, vertex.y).into());
+        }
+
+        b.end(true);
+    }
+}
+
+/// Removes vertices that are duplicates of, or colinear with, their neighbours.
+fn remove_colinear(mut vertices: Vec<Vec2>) -> Vec<Vec2> {
+    vertices.dedup();
+    while vertices.len() > 1 && vertices.first() == vertices.last() {
+        vertices.pop();
+    }
+
+    let mut index = 0;
+    while vertices.len() >= 3 && index < vertices.len() {
+        let len = vertices.len();
+        let before = vertices[(index + len - 1) % len];
+        let after = vertices[(index + 1) % len];
+        if (vertices[index] - before)
+            .perp_dot(after - vertices[index])
+            .abs()
+            <= f32::EPSILON
+        {
+            vertices.remove(index);
+        } else {
+            index += 1;
+        }
+    }
+
+    vertices
+}
+
+/// The centroid of the area enclosed by `vertices`.
+fn centroid(vertices: &[Vec2]) -> Vec2 {
+    let (weighted_sum, area) =
+        polygon::EdgesIterator::new(vertices).fold((Vec2::ZERO, 0.0), |(sum, area), edge| {
+            let cross = edge.start.perp_dot(edge.end);
+            (sum + ((edge.start + edge.end) * cross), area + cross)
+        });
+
+    if area.abs() <= f32::EPSILON {
+        vertices.iter().sum::<Vec2>() / vertices.len() as f32
+    } else {
+        weighted_sum / (3.0 * area)
+    }
+}
+
+fn bounding_radius(vertices: &[Vec2], centroid: Vec2) -> f32 {
+    vertices
+        .iter()
+        .map(|vertex| vertex.distance_squared(centroid))
+        .fold(0.0, f32::max)
+        .sqrt()
+}
@@ -0,0 +1,190 @@
+use super::*;
+
+/// The result of [`Collider::distance`].
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub struct DistanceResult {
+    /// The distance between the colliders, which is negative when they overlap, in which case it's the depth of
+    /// the overlap.
+    pub distance: f32,
+    /// The closest point on `us` to `them`, or when overlapping, the point on `us` that is deepest inside `them`.
+    pub us: Vec2,
+    /// The closest point on `them` to `us`, or when overlapping, the point on `them` that is deepest inside `us`.
+    pub them: Vec2,
+    /// The unit normal pointing from `us` towards `them`.
+    pub normal: Vec2,
+}
+
+/// The distance between two colliders in world space, see [`Collider::distance`].
+pub(crate) fn distance(us: &Collider, them: &Collider) -> DistanceResult {
+    let contact = us
+        .collide(them)
+        .or_else(|| separation(us, them))
+        .and_then(|manifold| manifold.contact_points().next());
+
+    match contact {
+        Some(contact) => DistanceResult {
+            distance: -contact.depth(),
+            us: contact.us,
+            them: contact.them,
+            normal: contact.normal,
+        },
+        None => {
+            // Only reachable when rounding errors leave the colliders touching without registering a collision
+            let direction = them.aabb().center() - us.aabb().center();
+            let point = us.support_point(direction);
+            DistanceResult {
+                distance: 0.0,
+                us: point,
+                them: point,
+                normal: direction.normalize_or_zero(),
+            }
+        }
+    }
+}
+
+/// The closest point on the outline of `collider` to `point`, see [`Collider::project_point`].
+pub(crate) fn project_point(collider: &Collider, point: Vec2) -> (Vec2, bool, FeatureId) {
+    let round = |core: Vec2, radius: f32, fallback: Vec2| {
+        let direction = (point - core).try_normalize().unwrap_or(fallback);
+        core + (direction * radius)
+    };
+
+    let (closest, is_inside) = match collider {
+        Collider::Capsule(capsule) => {
+            let core = capsule.line.closest_point_to_point(&point);
+            let fallback = capsule.line.perp().try_normalize().unwrap_or(Vec2::Y);
+            (
+                round(core, capsule.radius, fallback),
+                capsule.contains(point),
+            )
+        }
+        Collider::Circle(circle) => (
+            round(circle.position, circle.radius, Vec2::Y),
+            circle.contains(point),
+        ),
+        Collider::ConvexPolygon(polygon) => {
+            let closest = polygon
+                .edges()
+                .map(|edge| edge.closest_point_to_point(&point))
+                .min_by(|a, b| {
+                    a.distance_squared(point)
+                        .total_cmp(&b.distance_squared(point))
+                })
+                .unwrap_or(point);
+            (closest, polygon.contains(point))
+        }
+        Collider::Point(Point(position)) => (*position, *position == point),
+        Collider::Polygon(polygon) => polygon.closest_point_on_perimeter(point),
+        Collider::Rectangle(rect) => rect.closest_point_on_perimeter(point),
+        Collider::Custom(shape) => {
+            let target = Point(point);
+            match gjk::closest_points(shape.as_ref(), &target) {
+                Some((closest, _)) => (closest, false),
+                None => {
+                    // The point is inside (or on) the shape, so the closest point on its outline is the one that
+                    // would push it out with the least movement
+                    let closest = algorithms::collide_shapes(shape.as_ref(), &target)
+                        .and_then(|manifold| manifold.contact_points().next())
+                        .map_or(point, |contact| contact.us);
+                    (closest, true)
+                }
+            }
+        }
+    };
+
+    (closest, is_inside, collider.feature_at(closest))
+}
+
+/// The parts of a collider that distances are measured from: a set of lines (where points are lines of zero
+/// length), which the outline of the collider surrounds at a distance of `radius`.
+///
+/// Returns `None` for custom shapes, which only support [`gjk::closest_points`].
+fn skeleton(collider: &Collider) -> Option<(Vec<Line>, f32)> {
+    match collider {
+        Collider::Capsule(capsule) => Some((vec![capsule.line], capsule.radius)),
+        Collider::Circle(circle) => Some((
+            vec![Line::new(circle.position, circle.position)],
+            circle.radius,
+        )),
+        Collider::ConvexPolygon(polygon) => Some((polygon.edges().collect(), 0.0)),
+        Collider::Point(point) => Some((vec![Line::new(point.0, point.0)], 0.0)),
+        Collider::Polygon(polygon) => Some((polygon.edges().collect(), 0.0)),
+        Collider::Rectangle(rect) => Some((ConvexPolygon::from(*rect).edges().collect(), 0.0)),
+        Collider::Custom(_) => None,
+    }
+}
+
+/// A line between the closest points on `a` and `b`, which may both be points.
+fn closest_points(a: &Line, b: &Line) -> Line {
+    match (a.is_point(), b.is_point()) {
+        (true, true) => Line::new(a.start, b.start),
+        (true, false) => Line::new(a.start, b.closest_point_to_point(&a.start)),
+        (false, true) => Line::new(a.closest_point_to_point(&b.start), b.start),
+        (false, false) => a.closest_point_to_line(b),
+    }
+}
+
+/// The contact between the closest points of two colliders that aren't overlapping, which has a negative depth
+/// equal to the distance between them.
+///
+/// The colliders must not be overlapping, as the result is meaningless when one is entirely inside the other.
+/// Returns `None` if they are touching.
+pub(crate) fn separation(us: &Collider, them: &Collider) -> Option<ContactManifold> {
+    let ((us_lines, us_radius), (them_lines, them_radius)) = match (skeleton(us), skeleton(them)) {
+        (Some(us), Some(them)) => (us, them),
+        _ => {
+            let (us_point, them_point) = gjk::closest_points(us, them)?;
+            let normal = (them_point - us_point).try_normalize()?;
+            return Some(ContactManifold::point(us_point, them_point, normal));
+        }
+    };
+
+    // The closest pair of lines, preferring parallel lines when several pairs are equally close so that shapes
+    // resting flat against each other produce an edge contact
+    let is_parallel =
+        |a: &Line, b: &Line| !a.is_point() && !b.is_point() && a.is_nearly_parallel_to(b);
+    let (us_line, them_line, closest) = us_lines
+        .iter()
+        .flat_map(|us_line| {
+            them_lines
+                .iter()
+                .map(move |them_line| (us_line, them_line, closest_points(us_line, them_line)))
+        })
+        .min_by(|(a_us, a_them, a), (b_us, b_them, b)| {
+            let (a_distance, b_distance) = (a.length(), b.length());
+            if (a_distance - b_distance).abs() <= 1e-4 * a_distance.max(1.0) {
+                is_parallel(b_us, b_them).cmp(&is_parallel(a_us, a_them))
+            } else {
+                a_distance.total_cmp(&b_distance)
+            }
+        })?;
+
+    if closest.length() <= us_radius + them_radius {
+        return None;
+    }
+
+    let normal = closest.as_difference().normalize();
+    if is_parallel(us_line, them_line) {
+        if let Some(manifold) =
+            algorithms::parallel_line_contact(us_line, us_radius, them_line, them_radius, normal)
+        {
+            return Some(manifold);
+        }
+    }
+
+    Some(ContactManifold::point(
+        closest.start + (normal * us_radius),
+        closest.end - (normal * them_radius),
+        normal,
+    ))
+}
+
+/// Whether or not the bounding boxes of `us` and `them` are further than `margin` apart.
+pub(crate) fn aabbs_are_further_than(us: &dyn Shape, them: &dyn Shape, margin: f32) -> bool {
+    let us = us.aabb();
+    let them = them.aabb();
+    us.min().x > them.max().x + margin
+        || them.min().x > us.max().x + margin
+        || us.min().y > them.max().y + margin
+        || them.min().y > us.max().y + margin
+}
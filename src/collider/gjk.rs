@@ -0,0 +1,520 @@
+use super::*;
+
+const MAX_ITERATIONS: usize = 64;
+const TOLERANCE: f32 = 1e-4;
+
+/// A point on the Minkowski difference of two shapes, along with the points on each shape that produced it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SupportPoint {
+    pub point: Vec2,
+    pub us: Vec2,
+    pub them: Vec2,
+}
+
+impl SupportPoint {
+    pub fn new(us: &dyn Shape, them: &dyn Shape, direction: Vec2) -> Self {
+        let us = us.support_point(direction);
+        let them = them.support_point(-direction);
+        Self {
+            point: us - them,
+            us,
+            them,
+        }
+    }
+}
+
+/// Checks whether or not the convex hulls of `us` and `them` intersect, using the GJK algorithm.
+///
+/// If they do, returns a simplex of up to 3 points on their Minkowski difference that encloses the origin.
+/// Shapes that are only just touching count as intersecting.
+///
+/// Based on https://web.archive.org/web/20201109034236/https://blog.winter.dev/2020/gjk-algorithm/
+pub fn intersect(us: &dyn Shape, them: &dyn Shape) -> Option<Vec<SupportPoint>> {
+    let mut direction = them.support_point(Vec2::X) - us.support_point(Vec2::X);
+    if direction.length_squared() <= f32::EPSILON {
+        direction = Vec2::X;
+    }
+
+    let first = SupportPoint::new(us, them, direction);
+    let mut simplex = vec![first];
+    direction = -first.point;
+
+    for _ in 0..MAX_ITERATIONS {
+        if direction.length_squared() <= f32::EPSILON * f32::EPSILON {
+            // The origin lies on the simplex
+            return Some(simplex);
+        }
+
+        let support = SupportPoint::new(us, them, direction);
+        if support.point.dot(direction) < 0.0 {
+            // The furthest point in the search direction doesn't pass the origin, so it can't be enclosed
+            return None;
+        }
+
+        simplex.push(support);
+        if let Some(enclosing) = next_simplex(&mut simplex, &mut direction) {
+            return enclosing.then_some(simplex);
+        }
+    }
+
+    // Failing to converge can only happen when the origin is (very nearly) on the boundary
+    Some(simplex)
+}
+
+/// Finds the closest points on the convex hulls of `us` and `them`, using the distance variant of the GJK
+/// algorithm.
+///
+/// Returns `None` if the shapes are intersecting or touching.
+pub fn closest_points(us: &dyn Shape, them: &dyn Shape) -> Option<(Vec2, Vec2)> {
+    let mut direction = them.support_point(Vec2::X) - us.support_point(Vec2::X);
+    if direction.length_squared() <= f32::EPSILON {
+        direction = Vec2::X;
+    }
+
+    let mut simplex = vec![SupportPoint::new(us, them, direction)];
+
+    for _ in 0..MAX_ITERATIONS {
+        let (reduced, weights) = closest_on_simplex(&simplex)?;
+        let closest = weighted_sum(&reduced, &weights, |point| point.point);
+
+        let distance_squared = closest.length_squared();
+        if distance_squared <= f32::EPSILON * f32::EPSILON {
+            return None;
+        }
+
+        let support = SupportPoint::new(us, them, -closest);
+        let is_new = reduced
+            .iter()
+            .all(|point| point.point.distance_squared(support.point) > f32::EPSILON);
+
+        simplex = reduced;
+        if !is_new
+            || distance_squared - support.point.dot(closest) <= TOLERANCE * distance_squared.sqrt()
+        {
+            // The support point doesn't get any closer to the origin
+            break;
+        }
+
+        simplex.push(support);
+    }
+
+    let (simplex, weights) = closest_on_simplex(&simplex)?;
+    Some((
+        weighted_sum(&simplex, &weights, |point| point.us),
+        weighted_sum(&simplex, &weights, |point| point.them),
+    ))
+}
+
+/// Casts a ray against the convex hull of `shape`, using the GJK ray cast algorithm.
+///
+/// Based on "Ray Casting against General Convex Objects with Application to Continuous Collision Detection" by
+/// Gino van den Bergen.
+pub fn cast_ray<S: Shape + ?Sized>(
+    shape: &S,
+    ray: &Ray2d,
+    max_toi: f32,
+    solid: bool,
+) -> Option<RayHit> {
+    let mut toi = 0.0;
+    let mut point = ray.origin;
+    let mut normal = Vec2::ZERO;
+
+    // The points on `shape` whose offsets from `point` form the simplex
+    let mut vertices: Vec<Vec2> = Vec::new();
+    let mut closest = point - shape.support_point(ray.direction);
+
+    for _ in 0..MAX_ITERATIONS {
+        if closest.length_squared() <= TOLERANCE * TOLERANCE {
+            break;
+        }
+
+        let support = shape.support_point(closest);
+        let offset = point - support;
+        let progress = closest.dot(offset);
+        if progress > 0.0 {
+            // `point` lies in front of the plane through `support`, so advance it to that plane
+            let approach = closest.dot(ray.direction);
+            if approach >= 0.0 {
+                return None;
+            }
+
+            toi -= progress / approach;
+            if toi > max_toi {
+                return None;
+            }
+
+            point = ray.point_at(toi);
+            normal = closest;
+        }
+
+        if vertices
+            .iter()
+            .all(|vertex| vertex.distance_squared(support) > f32::EPSILON)
+        {
+            vertices.push(support);
+        }
+
+        let simplex: Vec<SupportPoint> = vertices
+            .iter()
+            .map(|&vertex| SupportPoint {
+                point: point - vertex,
+                us: point,
+                them: vertex,
+            })
+            .collect();
+
+        let Some((reduced, weights)) = closest_on_simplex(&simplex) else {
+            break;
+        };
+        vertices = reduced.iter().map(|support| support.them).collect();
+        closest = weighted_sum(&reduced, &weights, |support| support.point);
+    }
+
+    if toi > 0.0 {
+        return Some(RayHit::new(ray, toi, normal));
+    }
+
+    // The ray starts inside `shape`
+    if solid {
+        return Some(RayHit::inside(ray));
+    }
+
+    if ray.direction.length_squared() <= f32::EPSILON {
+        return None;
+    }
+
+    // Find where the ray leaves `shape` by casting back towards its origin from somewhere outside of `shape`
+    let aabb = shape.aabb();
+    let reach =
+        (aabb.size().length() + aabb.center().distance(ray.origin)) / ray.direction.length();
+    let reverse = Ray2d::new(ray.point_at(reach), -ray.direction);
+    let exit = cast_ray(shape, &reverse, reach, true)?;
+
+    let toi = reach - exit.toi;
+    (toi <= max_toi).then(|| RayHit::new(ray, toi, exit.normal))
+}
+
+fn weighted_sum(
+    simplex: &[SupportPoint],
+    weights: &[f32],
+    point: impl Fn(&SupportPoint) -> Vec2,
+) -> Vec2 {
+    simplex
+        .iter()
+        .zip(weights)
+        .map(|(support, weight)| point(support) * *weight)
+        .sum()
+}
+
+/// Reduces `simplex` to the smallest set of points whose convex hull contains the point closest to the origin,
+/// along with the barycentric weights of that point.
+///
+/// Returns `None` if `simplex` encloses the origin.
+fn closest_on_simplex(simplex: &[SupportPoint]) -> Option<(Vec<SupportPoint>, Vec<f32>)> {
+    match *simplex {
+        [a] => Some((vec![a], vec![1.0])),
+        [a, b] => {
+            let ab = b.point - a.point;
+            let length_squared = ab.length_squared();
+            let t = if length_squared <= f32::EPSILON {
+                0.0
+            } else {
+                (-a.point.dot(ab) / length_squared).clamp(0.0, 1.0)
+            };
+
+            if t <= 0.0 {
+                Some((vec![a], vec![1.0]))
+            } else if t >= 1.0 {
+                Some((vec![b], vec![1.0]))
+            } else {
+                Some((vec![a, b], vec![1.0 - t, t]))
+            }
+        }
+        [a, b, c] => {
+            let ab = a.point.perp_dot(b.point);
+            let bc = b.point.perp_dot(c.point);
+            let ca = c.point.perp_dot(a.point);
+            if (ab >= 0.0 && bc >= 0.0 && ca >= 0.0) || (ab <= 0.0 && bc <= 0.0 && ca <= 0.0) {
+                return None;
+            }
+
+            [[a, b], [b, c], [c, a]]
+                .iter()
+                .filter_map(|edge| closest_on_simplex(edge))
+                .min_by(|(a, a_weights), (b, b_weights)| {
+                    let a = weighted_sum(a, a_weights, |point| point.point);
+                    let b = weighted_sum(b, b_weights, |point| point.point);
+                    a.length_squared().total_cmp(&b.length_squared())
+                })
+        }
+        _ => None,
+    }
+}
+
+/// Reduces `simplex` to the feature closest to the origin and updates the search direction.
+///
+/// Returns `Some(true)` once the simplex encloses the origin, `Some(false)` when no further progress can be made,
+/// or `None` to keep searching.
+fn next_simplex(simplex: &mut Vec<SupportPoint>, direction: &mut Vec2) -> Option<bool> {
+    match *simplex.as_slice() {
+        [b, a] => {
+            // `a` is the newest point
+            let ab = b.point - a.point;
+            let ao = -a.point;
+
+            if ab.dot(ao) > 0.0 {
+                let perp = ab.perp();
+                let side = perp.dot(ao);
+                if side.abs() <= f32::EPSILON {
+                    // The origin lies on the segment
+                    return Some(true);
+                }
+                *direction = perp * side.signum();
+            } else {
+                *simplex = vec![a];
+                *direction = ao;
+            }
+
+            None
+        }
+        [c, b, a] => {
+            let ab = b.point - a.point;
+            let ac = c.point - a.point;
+            let ao = -a.point;
+
+            // Perpendiculars of each edge, pointing away from the opposite vertex
+            let mut ab_perp = ab.perp();
+            if ab_perp.dot(ac) > 0.0 {
+                ab_perp = -ab_perp;
+            }
+
+            let mut ac_perp = ac.perp();
+            if ac_perp.dot(ab) > 0.0 {
+                ac_perp = -ac_perp;
+            }
+
+            if ab_perp.dot(ao) > 0.0 {
+                *simplex = vec![b, a];
+                *direction = ab_perp;
+                None
+            } else if ac_perp.dot(ao) > 0.0 {
+                *simplex = vec![c, a];
+                *direction = ac_perp;
+                None
+            } else {
+                Some(true)
+            }
+        }
+        _ => Some(false),
+    }
+}
+
+/// Computes the penetration of two intersecting shapes from the simplex produced by [`intersect`], using the
+/// expanding polytope algorithm.
+///
+/// Returns the contact points on each shape and the normal pointing from `us` towards `them`, or `None` if the
+/// overlap is degenerate (e.g. between two points or lines) and has no meaningful direction.
+pub fn penetration(
+    us: &dyn Shape,
+    them: &dyn Shape,
+    simplex: Vec<SupportPoint>,
+) -> Option<(Vec2, Vec2, Vec2)> {
+    let mut polytope = simplex;
+
+    // Grow degenerate simplices into a triangle
+    for direction in [Vec2::X, Vec2::Y, Vec2::NEG_X, Vec2::NEG_Y] {
+        if polytope.len() >= 3 {
+            break;
+        }
+
+        let direction = match polytope.as_slice() {
+            &[a, b] => {
+                let perp = (b.point - a.point).perp();
+                if perp.length_squared() <= f32::EPSILON || perp.dot(direction) < 0.0 {
+                    direction
+                } else {
+                    perp
+                }
+            }
+            _ => direction,
+        };
+
+        let support = SupportPoint::new(us, them, direction);
+        let is_new = polytope
+            .iter()
+            .all(|point| point.point.distance_squared(support.point) > f32::EPSILON);
+        let is_colinear = match polytope.as_slice() {
+            &[a, b] => (b.point - a.point).perp_dot(support.point - a.point).abs() <= f32::EPSILON,
+            _ => false,
+        };
+
+        if is_new && !is_colinear {
+            polytope.push(support);
+        }
+    }
+
+    if polytope.len() < 3 {
+        return None;
+    }
+
+    // Make the polytope wind anticlockwise, so that edge normals are `(edge.y, -edge.x)`
+    let winding =
+        (polytope[1].point - polytope[0].point).perp_dot(polytope[2].point - polytope[0].point);
+    if winding < 0.0 {
+        polytope.swap(0, 1);
+    }
+
+    for _ in 0..MAX_ITERATIONS {
+        let (index, normal, distance) = closest_edge(&polytope)?;
+        let support = SupportPoint::new(us, them, normal);
+
+        if support.point.dot(normal) - distance <= TOLERANCE {
+            return Some(witness_points(&polytope, index, normal, distance));
+        }
+
+        polytope.insert(index + 1, support);
+    }
+
+    let (index, normal, distance) = closest_edge(&polytope)?;
+    Some(witness_points(&polytope, index, normal, distance))
+}
+
+/// The index, outward normal and distance from the origin of the edge of `polytope` closest to the origin.
+fn closest_edge(polytope: &[SupportPoint]) -> Option<(usize, Vec2, f32)> {
+    (0..polytope.len())
+        .filter_map(|index| {
+            let a = polytope[index].point;
+            let b = polytope[(index + 1) % polytope.len()].point;
+            let edge = b - a;
+            let normal = Vec2::new(edge.y, -edge.x).try_normalize()?;
+            Some((index, normal, normal.dot(a)))
+        })
+        .min_by(|(_, _, a), (_, _, b)| a.total_cmp(b))
+}
+
+/// The contact points on each shape that correspond to the closest point on the given edge to the origin.
+fn witness_points(
+    polytope: &[SupportPoint],
+    index: usize,
+    normal: Vec2,
+    distance: f32,
+) -> (Vec2, Vec2, Vec2) {
+    let a = polytope[index];
+    let b = polytope[(index + 1) % polytope.len()];
+
+    let edge = Line::new(a.point, b.point);
+    let closest = normal * distance;
+    let t = if edge.length_squared() <= f32::EPSILON {
+        0.0
+    } else {
+        ((closest - a.point).dot(edge.as_difference()) / edge.length_squared()).clamp(0.0, 1.0)
+    };
+
+    (a.us.lerp(b.us, t), a.them.lerp(b.them, t), normal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A disk that only describes itself through the [`Shape`] trait, so it always takes the generic path.
+    #[derive(Debug)]
+    struct Disk(Circle);
+
+    impl Shape for Disk {
+        fn support_point(&self, direction: Vec2) -> Vec2 {
+            self.0.position + (direction.normalize_or_zero() * self.0.radius)
+        }
+
+        fn contains(&self, point: Vec2) -> bool {
+            self.0.contains(point)
+        }
+    }
+
+    fn u_shape() -> Polygon {
+        Polygon::new(vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(6.0, 0.0),
+            Vec2::new(6.0, 6.0),
+            Vec2::new(4.0, 6.0),
+            Vec2::new(4.0, 2.0),
+            Vec2::new(2.0, 2.0),
+            Vec2::new(2.0, 6.0),
+            Vec2::new(0.0, 6.0),
+        ])
+    }
+
+    #[test]
+    fn penetration_of_overlapping_rects() {
+        let us = Rectangle::new(0.0, 0.0, 2.0, 2.0);
+        let them = Rectangle::new(1.5, 0.5, 3.5, 1.5);
+
+        let manifold = algorithms::collide_shapes(&us, &them).unwrap();
+        assert!((manifold.depth() - 0.5).abs() < 1e-4);
+        assert!(manifold.normal().abs_diff_eq(Vec2::X, 1e-4));
+    }
+
+    #[test]
+    fn penetration_of_overlapping_circles() {
+        let us = Circle::new(Vec2::ZERO, 1.0);
+        let them = Circle::new(Vec2::new(0.0, -1.5), 1.0);
+
+        let manifold = algorithms::collide_shapes(&us, &them).unwrap();
+        assert!((manifold.depth() - 0.5).abs() < 1e-2);
+        assert!(manifold.normal().abs_diff_eq(Vec2::NEG_Y, 1e-2));
+    }
+
+    #[test]
+    fn separated_shapes() {
+        let us = Circle::new(Vec2::ZERO, 1.0);
+        let them = Rectangle::new(2.0, -1.0, 3.0, 1.0);
+
+        assert!(intersect(&us, &them).is_none());
+        let (us_point, them_point) = closest_points(&us, &them).unwrap();
+        assert!(us_point.abs_diff_eq(Vec2::X, 1e-3));
+        assert!(them_point.abs_diff_eq(Vec2::new(2.0, 0.0), 1e-3));
+    }
+
+    #[test]
+    fn custom_shape_in_concave_notch() {
+        let disk = Circle::new(Vec2::new(3.0, 4.0), 0.5);
+        let polygon = Collider::Polygon(u_shape());
+
+        assert!(Collider::Circle(disk).collide(&polygon).is_none());
+        assert!(Collider::custom(Disk(disk)).collide(&polygon).is_none());
+        assert!(polygon.collide(&Collider::custom(Disk(disk))).is_none());
+    }
+
+    #[test]
+    fn custom_shape_overlapping_concave_polygon() {
+        let disk = Collider::custom(Disk(Circle::new(Vec2::new(1.0, 6.25), 0.5)));
+        let polygon = Collider::Polygon(u_shape());
+
+        let manifold = disk.collide(&polygon).unwrap();
+        assert!(manifold.depth() > 0.0);
+        assert!(manifold.normal().y < 0.0);
+    }
+
+    #[test]
+    fn custom_shape_is_only_pushed_out_through_the_outline() {
+        let polygon = Collider::Polygon(u_shape());
+        let is_outline_normal = |normal: Vec2| {
+            [Vec2::X, Vec2::NEG_X, Vec2::Y, Vec2::NEG_Y]
+                .iter()
+                .any(|axis| normal.abs_diff_eq(*axis, 1e-4))
+        };
+
+        // Straddling the diagonals between the convex pieces, both inside and across the outline
+        for (position, depth) in [
+            (Vec2::new(3.0, 1.0), 1.5),
+            (Vec2::new(1.0, 3.0), 1.5),
+            (Vec2::new(5.0, 1.2), 1.5),
+            (Vec2::new(3.0, 1.8), 0.7),
+        ] {
+            let disk = Collider::custom(Disk(Circle::new(position, 0.5)));
+            let manifold = disk.collide(&polygon).unwrap();
+            assert!(is_outline_normal(manifold.normal()), "{position}");
+            assert!((manifold.depth() - depth).abs() < 1e-3, "{position}");
+        }
+    }
+}
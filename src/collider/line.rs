@@ -45,6 +45,13 @@ impl Line {
         self.as_difference().perp_dot(other.as_difference()).abs() <= f32::EPSILON
     }
 
+    /// Like [`Line::is_parallel_to`], but tolerant of the rounding errors that build up when transforming lines.
+    pub fn is_nearly_parallel_to(&self, other: &Self) -> bool {
+        let direction = self.as_difference().normalize_or_zero();
+        let other_direction = other.as_difference().normalize_or_zero();
+        direction.perp_dot(other_direction).abs() <= 1e-3
+    }
+
     pub fn is_vertical(&self) -> bool {
         (self.start.x - self.end.x).abs() <= f32::EPSILON
     }
@@ -187,6 +194,38 @@ impl Line {
         Some((ca, cb))
     }
 
+    /// Checks whether or not `self` and `other` share any points.
+    ///
+    /// This is cheaper than [`Line::intersect_line`] when the point of intersection isn't needed.
+    pub fn intersects_line(&self, other: &Self) -> bool {
+        fn orientation(a: Vec2, b: Vec2, c: Vec2) -> f32 {
+            (b - a).perp_dot(c - a)
+        }
+
+        fn in_bounds(line: &Line, point: Vec2) -> bool {
+            point.cmpge(line.start.min(line.end)).all()
+                && point.cmple(line.start.max(line.end)).all()
+        }
+
+        let d1 = orientation(other.start, other.end, self.start);
+        let d2 = orientation(other.start, other.end, self.end);
+        let d3 = orientation(self.start, self.end, other.start);
+        let d4 = orientation(self.start, self.end, other.end);
+
+        if ((d1 > 0.0 && d2 < 0.0) || (d1 < 0.0 && d2 > 0.0))
+            && ((d3 > 0.0 && d4 < 0.0) || (d3 < 0.0 && d4 > 0.0))
+        {
+            // The lines properly cross each other
+            return true;
+        }
+
+        // Otherwise, an endpoint of one line must lie on the other
+        (d1 == 0.0 && in_bounds(other, self.start))
+            || (d2 == 0.0 && in_bounds(other, self.end))
+            || (d3 == 0.0 && in_bounds(self, other.start))
+            || (d4 == 0.0 && in_bounds(self, other.end))
+    }
+
     /// Computes the intersection between `self` and `other`, if any exists.
     ///
     /// From https://web.archive.org/web/20210428000731/http://geomalgorithms.com/a05-_intersect-1.html
@@ -434,15 +473,46 @@ impl Transformable for Line {
     }
 }
 
+impl Shape for Line {
+    fn support_point(&self, direction: Vec2) -> Vec2 {
+        if self.start.dot(direction) >= self.end.dot(direction) {
+            self.start
+        } else {
+            self.end
+        }
+    }
+
+    fn aabb(&self) -> Rectangle {
+        Rectangle::from_corners(self.start, self.end)
+    }
+
+    fn contains(&self, point: Vec2) -> bool {
+        self.distance_to_point_squared(&point) <= f32::EPSILON * f32::EPSILON
+    }
+
+    fn feature_at(&self, point: Vec2) -> FeatureId {
+        polygon::segment_feature(self, 0, 1, point)
+    }
+
+    fn cast_ray(&self, ray: &Ray2d, max_toi: f32, _solid: bool) -> Option<RayHit> {
+        ray::cast_ray_edges(ray, [*self], max_toi, false, false)
+    }
+
+    #[cfg(feature = "debug-draw")]
+    fn debug_geometry(&self, b: &mut Builder) {
+        self.add_geometry(b);
+    }
+}
+
 impl Collides<Capsule> for Line {
     fn collide(&self, other: &Capsule) -> Option<ContactManifold> {
-        other.collide(self)
+        algorithms::collide_capsule_line(other, self).map(|manifold| -manifold)
     }
 }
 
 impl Collides<Circle> for Line {
     fn collide(&self, other: &Circle) -> Option<ContactManifold> {
-        other.collide(self)
+        algorithms::collide_circle_line(other, self).map(|manifold| -manifold)
     }
 }
 
@@ -454,25 +524,19 @@ impl Collides<Line> for Line {
 
 impl Collides<Point> for Line {
     fn collide(&self, other: &Point) -> Option<ContactManifold> {
-        (self.distance_to_point_squared(&other.0) <= f32::EPSILON * f32::EPSILON).into()
+        algorithms::collide_line_point(self, other)
     }
 }
 
 impl Collides<Polygon> for Line {
     fn collide(&self, other: &Polygon) -> Option<ContactManifold> {
-        other.collide(self)
+        algorithms::collide_line_polygon(self, other)
     }
 }
 
 impl Collides<Rectangle> for Line {
     fn collide(&self, other: &Rectangle) -> Option<ContactManifold> {
-        (other.contains(self.start)
-            || other.contains(self.end)
-            || self.collide(&other.left()).colliding
-            || self.collide(&other.top()).colliding
-            || self.collide(&other.right()).colliding
-            || self.collide(&other.bottom()).colliding)
-            .into()
+        algorithms::collide_line_rect(self, other)
     }
 }
 
@@ -0,0 +1,276 @@
+use super::*;
+
+/// How the corners of an offset polygon are joined together.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JoinStyle {
+    /// Extends the offset edges until they meet at a sharp corner.
+    ///
+    /// The value is the miter limit: corners whose miter would extend further than this many times the offset
+    /// distance away from the original vertex are bevelled instead.
+    Miter(f32),
+    /// Joins the offset edges with a circular arc around the original vertex.
+    ///
+    /// The value is the arc tolerance: the maximum distance between the true arc and its approximating segments.
+    Round(f32),
+    /// Joins the offset edges with a single straight line.
+    Bevel,
+}
+
+impl Default for JoinStyle {
+    fn default() -> Self {
+        Self::Miter(2.0)
+    }
+}
+
+/// Offsets the closed outline formed by `vertices` by `distance`, see [`Polygon::offset`].
+pub(crate) fn offset_outline(
+    vertices: &[Vec2],
+    distance: f32,
+    join_style: JoinStyle,
+) -> Vec<Vec<Vec2>> {
+    let mut vertices = vertices.to_vec();
+    vertices.dedup();
+    if vertices.len() > 1 && vertices.first() == vertices.last() {
+        vertices.pop();
+    }
+
+    if vertices.len() < 3 {
+        return Vec::new();
+    }
+
+    let area = signed_area(&vertices);
+    if area == 0.0 {
+        return Vec::new();
+    }
+
+    if distance == 0.0 {
+        return vec![vertices];
+    }
+
+    // Outward facing normals, regardless of the winding of the outline
+    let orientation = area.signum();
+    let normals: Vec<Vec2> = polygon::EdgesIterator::new(&vertices)
+        .map(|edge| (-edge.perp() * orientation).normalize_or_zero())
+        .collect();
+
+    let len = vertices.len();
+    let mut raw = Vec::with_capacity(len * 2);
+    for (index, &vertex) in vertices.iter().enumerate() {
+        let before = normals[(index + len - 1) % len];
+        let after = normals[index];
+
+        let sin = before.perp_dot(after) * orientation;
+        let cos = before.dot(after);
+
+        if sin.abs() <= f32::EPSILON && cos > 0.0 {
+            // The edges are colinear
+            raw.push(vertex + (after * distance));
+        } else if sin * distance > 0.0 {
+            // The offset edges diverge from each other, so the gap between them needs filling
+            add_join(&mut raw, vertex, before, after, distance, join_style);
+        } else {
+            // The offset edges overlap each other; going via the original vertex creates a loop that gets
+            // discarded later on
+            raw.push(vertex + (before * distance));
+            raw.push(vertex);
+            raw.push(vertex + (after * distance));
+        }
+    }
+
+    let epsilon = distance.abs() * 1e-3 + 1e-4;
+    let original = Polygon::new(vertices);
+    split_into_simple_loops(raw)
+        .into_iter()
+        .filter(|outline| {
+            // Keep only the loops that wind the same way as the original outline, and that lie the full offset
+            // distance away from it on the correct side
+            signed_area(outline) * orientation > epsilon
+                && outline.iter().all(|&point| {
+                    let far_enough = original
+                        .edges()
+                        .all(|edge| edge.distance_to_point(&point) >= distance.abs() - epsilon);
+                    far_enough && original.contains(point) == (distance < 0.0)
+                })
+        })
+        .collect()
+}
+
+/// Twice the area enclosed by `vertices`, which is positive for anticlockwise outlines and negative for
+/// clockwise outlines.
+fn signed_area(vertices: &[Vec2]) -> f32 {
+    polygon::EdgesIterator::new(vertices)
+        .map(|edge| edge.start.perp_dot(edge.end))
+        .sum()
+}
+
+fn add_join(
+    raw: &mut Vec<Vec2>,
+    vertex: Vec2,
+    before: Vec2,
+    after: Vec2,
+    distance: f32,
+    join_style: JoinStyle,
+) {
+    match join_style {
+        JoinStyle::Miter(limit) => {
+            // The miter point lies along the bisector of the normals, 1 / cos(θ / 2) times further out than the
+            // offset edges
+            let cos = before.dot(after);
+            let miter_ratio = (2.0 / (1.0 + cos)).sqrt();
+            if miter_ratio <= limit {
+                raw.push(vertex + ((before + after) * (distance / (1.0 + cos))));
+            } else {
+                raw.push(vertex + (before * distance));
+                raw.push(vertex + (after * distance));
+            }
+        }
+        JoinStyle::Round(tolerance) => {
+            let radius = distance.abs();
+            let angle = before.perp_dot(after).atan2(before.dot(after));
+            let max_step = 2.0 * (1.0 - (tolerance / radius).min(1.0)).acos();
+            let steps = (angle.abs() / max_step.max(0.01)).ceil().max(1.0) as u32;
+
+            let rotation = Vec2::from_angle(angle / steps as f32);
+            let mut normal = before;
+            raw.push(vertex + (normal * distance));
+            for _ in 0..steps {
+                normal = rotation.rotate(normal);
+                raw.push(vertex + (normal * distance));
+            }
+        }
+        JoinStyle::Bevel => {
+            raw.push(vertex + (before * distance));
+            raw.push(vertex + (after * distance));
+        }
+    }
+}
+
+/// Splits a closed outline at every point where it crosses itself, producing a set of simple outlines.
+fn split_into_simple_loops(outline: Vec<Vec2>) -> Vec<Vec<Vec2>> {
+    let mut pending = vec![outline];
+    let mut loops = Vec::new();
+
+    while let Some(mut outline) = pending.pop() {
+        outline.dedup();
+        while outline.len() > 1 && outline.first() == outline.last() {
+            outline.pop();
+        }
+
+        if outline.len() < 3 {
+            continue;
+        }
+
+        match find_crossing(&outline) {
+            Some((i, j, point)) => {
+                // Both halves are strictly shorter than the outline, so this always terminates
+                let mut inner = outline[(i + 1)..=j].to_vec();
+                inner.push(point);
+
+                let mut outer = outline[..=i].to_vec();
+                outer.push(point);
+                outer.extend_from_slice(&outline[(j + 1)..]);
+
+                pending.push(inner);
+                pending.push(outer);
+            }
+            None => loops.push(outline),
+        }
+    }
+
+    loops
+}
+
+/// Finds the first pair of non-adjacent edges of `outline` that intersect, as well as their intersection point.
+fn find_crossing(outline: &[Vec2]) -> Option<(usize, usize, Vec2)> {
+    let edges: Vec<Line> = polygon::EdgesIterator::new(outline).collect();
+    let len = edges.len();
+
+    for i in 0..len {
+        for j in (i + 2)..len {
+            if i == 0 && j == len - 1 {
+                continue;
+            }
+
+            if let Some(point) = crossing_point(&edges[i], &edges[j]) {
+                return Some((i, j, point));
+            }
+        }
+    }
+
+    None
+}
+
+/// The point at which the segments `a` and `b` cross, if any.
+fn crossing_point(a: &Line, b: &Line) -> Option<Vec2> {
+    if !a.intersects_line(b) {
+        return None;
+    }
+
+    let u = a.as_difference();
+    let v = b.as_difference();
+    let denominator = u.perp_dot(v);
+    if denominator.abs() <= f32::EPSILON {
+        // Colinear overlapping segments, so any shared point will do
+        return Some(if b.intersects_colinear_point(&a.start) {
+            a.start
+        } else {
+            b.start
+        });
+    }
+
+    let t = (b.start - a.start).perp_dot(v) / denominator;
+    Some(a.parametric_point(t.clamp(0.0, 1.0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn area(polygon: &Polygon) -> f32 {
+        polygon
+            .edges()
+            .map(|edge| edge.start.perp_dot(edge.end))
+            .sum::<f32>()
+            .abs()
+            / 2.0
+    }
+
+    #[test]
+    fn grow_square_with_miter() {
+        let square = Polygon::from(ConvexPolygon::from(Rectangle::new(0.0, 0.0, 4.0, 4.0)));
+        let grown = square.offset(1.0, JoinStyle::Miter(2.0));
+
+        assert_eq!(grown.len(), 1);
+        assert!((area(&grown[0]) - 36.0).abs() < 1e-3);
+        assert!(grown[0].contains(Vec2::new(-0.9, -0.9)));
+    }
+
+    #[test]
+    fn shrinking_concave_polygon_splits_it() {
+        // Two 4x4 squares joined by a bridge that is 1 wide
+        let dumbbell = Polygon::new(vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(4.0, 0.0),
+            Vec2::new(4.0, 1.5),
+            Vec2::new(6.0, 1.5),
+            Vec2::new(6.0, 0.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(10.0, 4.0),
+            Vec2::new(6.0, 4.0),
+            Vec2::new(6.0, 2.5),
+            Vec2::new(4.0, 2.5),
+            Vec2::new(4.0, 4.0),
+            Vec2::new(0.0, 4.0),
+        ]);
+
+        let mut pieces = dumbbell.offset(-0.75, JoinStyle::Miter(2.0));
+        pieces.sort_by(|a, b| a.vertices[0].x.total_cmp(&b.vertices[0].x));
+
+        assert_eq!(pieces.len(), 2);
+        let centers = [Vec2::new(2.0, 2.0), Vec2::new(8.0, 2.0)];
+        for (piece, center) in pieces.iter().zip(centers) {
+            assert!((area(piece) - 6.25).abs() < 1e-3);
+            assert!(piece.contains(center));
+        }
+    }
+}
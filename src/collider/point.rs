@@ -31,21 +31,64 @@ impl Transformable for Point {
     }
 }
 
+impl Shape for Point {
+    fn support_point(&self, _direction: Vec2) -> Vec2 {
+        self.0
+    }
+
+    fn aabb(&self) -> Rectangle {
+        Rectangle::from_corners(self.0, self.0)
+    }
+
+    fn contains(&self, point: Vec2) -> bool {
+        self.distance_squared(point) <= f32::EPSILON * f32::EPSILON
+    }
+
+    fn feature_at(&self, _point: Vec2) -> FeatureId {
+        FeatureId::Vertex(0)
+    }
+
+    fn cast_ray(&self, ray: &Ray2d, max_toi: f32, _solid: bool) -> Option<RayHit> {
+        let length_squared = ray.direction.length_squared();
+        let toi = if length_squared <= f32::EPSILON {
+            0.0
+        } else {
+            ((self.0 - ray.origin).dot(ray.direction) / length_squared).max(0.0)
+        };
+
+        // Points have no area, so the ray has to pass (almost) exactly through `self` to hit it
+        let tolerance = 1e-5 * self.0.abs().max_element().max(1.0);
+        let hits = ray.point_at(toi).distance_squared(self.0) <= tolerance * tolerance;
+        (hits && toi <= max_toi).then(|| RayHit::new(ray, toi, -ray.direction))
+    }
+
+    #[cfg(feature = "debug-draw")]
+    fn debug_geometry(&self, b: &mut Builder) {
+        self.add_geometry(b);
+    }
+}
+
 impl Collides<Capsule> for Point {
     fn collide(&self, other: &Capsule) -> Option<ContactManifold> {
-        algorithms::collide_capsule_point(other, self).neg()
+        algorithms::collide_capsule_point(other, self).map(|manifold| -manifold)
     }
 }
 
 impl Collides<Circle> for Point {
     fn collide(&self, other: &Circle) -> Option<ContactManifold> {
-        algorithms::collide_circle_point(other, self).neg()
+        algorithms::collide_circle_point(other, self).map(|manifold| -manifold)
+    }
+}
+
+impl Collides<ConvexPolygon> for Point {
+    fn collide(&self, other: &ConvexPolygon) -> Option<ContactManifold> {
+        algorithms::collide_point_convex_polygon(self, other)
     }
 }
 
 impl Collides<Line> for Point {
     fn collide(&self, other: &Line) -> Option<ContactManifold> {
-        other.collide(self)
+        algorithms::collide_line_point(other, self).map(|manifold| -manifold)
     }
 }
 
@@ -57,7 +100,7 @@ impl Collides<Point> for Point {
 
 impl Collides<Polygon> for Point {
     fn collide(&self, other: &Polygon) -> Option<ContactManifold> {
-        other.collide(self)
+        algorithms::collide_point_polygon(self, other)
     }
 }
 
@@ -3,14 +3,29 @@ use crate::transform_ext::TransformPoint2;
 use std::iter::FusedIterator;
 
 /// An arbitrary polygon.
+///
+/// Whether it is convex is checked once on construction, so that collisions with convex polygons can take the
+/// same fast paths as [`ConvexPolygon`]. Changing `vertices` directly doesn't check it again, so build a new
+/// polygon instead.
 #[derive(Clone, Default, Debug, PartialEq)]
 pub struct Polygon {
     pub vertices: Vec<Vec2>,
+    convex: Option<ConvexPolygon>,
 }
 
 impl Polygon {
     pub fn new(vertices: Vec<Vec2>) -> Self {
-        Self { vertices }
+        let convex = ConvexPolygon::new(vertices.clone());
+        Self { vertices, convex }
+    }
+
+    /// `self` as a [`ConvexPolygon`], or `None` if it's concave.
+    pub fn as_convex(&self) -> Option<&ConvexPolygon> {
+        self.convex.as_ref()
+    }
+
+    pub fn is_convex(&self) -> bool {
+        self.convex.is_some()
     }
 
     pub fn edges(&self) -> EdgesIterator<'_> {
@@ -74,71 +89,230 @@ impl Polygon {
     pub fn contains(&self, point: Vec2) -> bool {
         self.winding_number(point) != 0
     }
+
+    /// The edge of `self` that is closest to the given point, and the closest point on that edge.
+    pub fn closest_edge(&self, point: Vec2) -> Option<(Line, Vec2)> {
+        self.edges()
+            .map(|edge| (edge, edge.closest_point_to_point(&point)))
+            .min_by(|(_, a), (_, b)| {
+                a.distance_squared(point)
+                    .total_cmp(&b.distance_squared(point))
+            })
+    }
+
+    /// The closest point on the perimeter of `self` to the given point, as well as a boolean indicating
+    /// whether the given point lies inside `self` or not.
+    pub fn closest_point_on_perimeter(&self, point: Vec2) -> (Vec2, bool) {
+        let closest_point = self
+            .closest_edge(point)
+            .map_or(point, |(_, closest_point)| closest_point);
+        (closest_point, self.contains(point))
+    }
+
+    /// The unit normal of the given edge of `self` that points out of `self`.
+    pub fn outward_normal(&self, edge: &Line) -> Vec2 {
+        let normal = edge.perp().normalize_or_zero();
+        let midpoint = edge.parametric_point(0.5);
+        let probe = edge.length().max(1.0) * 1e-3;
+        if self.contains(midpoint + (normal * probe)) {
+            -normal
+        } else {
+            normal
+        }
+    }
+
+    /// Whether or not `self` is a simple polygon, i.e. none of its edges intersect each other.
+    pub fn is_simple(&self) -> bool {
+        simplify::is_simple_outline(&self.vertices)
+    }
+
+    /// Simplifies `self` using the Douglas-Peucker algorithm, removing vertices that lie within `tolerance` of the
+    /// simplified outline.
+    ///
+    /// If `self` is simple then so is the result. See [`douglas_peucker_closed`].
+    pub fn simplify_douglas_peucker(&self, tolerance: f32) -> Self {
+        Self::new(simplify::douglas_peucker_closed(&self.vertices, tolerance))
+    }
+
+    /// Simplifies `self` using the Visvalingam-Whyatt algorithm, removing vertices that form a triangle with an
+    /// area smaller than `tolerance` with their neighbours.
+    ///
+    /// If `self` is simple then so is the result. See [`visvalingam_whyatt_closed`].
+    pub fn simplify_visvalingam_whyatt(&self, tolerance: f32) -> Self {
+        Self::new(simplify::visvalingam_whyatt_closed(
+            &self.vertices,
+            tolerance,
+        ))
+    }
+
+    /// Splits `self` into triangles by ear clipping, in either winding order.
+    ///
+    /// `self` should be simple. Degenerate corners, where the neighbouring edges are colinear, are skipped.
+    pub fn triangulate(&self) -> Vec<Triangle> {
+        let signed_area: f32 = self.edges().map(|edge| edge.start.perp_dot(edge.end)).sum();
+
+        let mut remaining: Vec<Vec2> = self.vertices.clone();
+        if signed_area < 0.0 {
+            remaining.reverse();
+        }
+
+        let mut triangles = Vec::with_capacity(remaining.len().saturating_sub(2));
+        while remaining.len() >= 3 {
+            let len = remaining.len();
+            let corner = |i: usize| {
+                [
+                    remaining[(i + len - 1) % len],
+                    remaining[i],
+                    remaining[(i + 1) % len],
+                ]
+            };
+
+            // An ear is a convex corner whose triangle contains none of the other vertices
+            let ear = (0..len).find(|&i| {
+                let [a, b, c] = corner(i);
+                let triangle = Triangle::new(a, b, c);
+                (b - a).perp_dot(c - b) > 0.0
+                    && remaining
+                        .iter()
+                        .all(|&p| p == a || p == b || p == c || !triangle.contains(p))
+            });
+
+            match ear {
+                Some(i) => {
+                    triangles.push(Triangle::from_array(corner(i)));
+                    remaining.remove(i);
+                }
+                None => {
+                    // Only degenerate corners can be left, which don't enclose any area
+                    let degenerate = (0..len).find(|&i| {
+                        let [a, b, c] = corner(i);
+                        (b - a).perp_dot(c - b) <= 0.0
+                    });
+                    match degenerate {
+                        Some(i) => {
+                            remaining.remove(i);
+                        }
+                        None => break,
+                    }
+                }
+            }
+        }
+
+        triangles
+    }
+
+    /// Grows (for positive distances) or shrinks (for negative distances) `self` by `distance`, using
+    /// `join_style` to fill in the gaps left at corners.
+    ///
+    /// `self` may be concave, in which case shrinking it can split it into several pieces, and growing it can
+    /// close off concavities; any holes this would produce are discarded. See also [`Rectangle::inset`].
+    pub fn offset(&self, distance: f32, join_style: JoinStyle) -> Vec<Self> {
+        offset::offset_outline(&self.vertices, distance, join_style)
+            .into_iter()
+            .map(Self::new)
+            .collect()
+    }
 }
 
 impl From<Vec<Vec2>> for Polygon {
     fn from(vertices: Vec<Vec2>) -> Self {
-        Self { vertices }
+        Self::new(vertices)
+    }
+}
+
+impl From<ConvexPolygon> for Polygon {
+    fn from(polygon: ConvexPolygon) -> Self {
+        Self {
+            vertices: polygon.vertices().to_vec(),
+            convex: Some(polygon),
+        }
     }
 }
 
 impl Transformable for Polygon {
     fn to_transformed(&self, transform: &GlobalTransform) -> Self {
-        Self::new(
-            self.vertices
+        // Transforms preserve convexity, so there's no need to check it again
+        Self {
+            vertices: self
+                .vertices
                 .iter()
                 .map(|&vertex| transform.transform_point2(vertex))
                 .collect(),
-        )
+            convex: self
+                .convex
+                .as_ref()
+                .map(|convex| convex.to_transformed(transform)),
+        }
+    }
+}
+
+impl Shape for Polygon {
+    /// Polygons are treated as their convex hull.
+    fn support_point(&self, direction: Vec2) -> Vec2 {
+        self.vertices
+            .iter()
+            .copied()
+            .max_by(|a, b| a.dot(direction).total_cmp(&b.dot(direction)))
+            .unwrap_or_default()
+    }
+
+    fn contains(&self, point: Vec2) -> bool {
+        Polygon::contains(self, point)
+    }
+
+    fn feature_at(&self, point: Vec2) -> FeatureId {
+        outline_feature(&self.vertices, point)
+    }
+
+    fn cast_ray(&self, ray: &Ray2d, max_toi: f32, solid: bool) -> Option<RayHit> {
+        ray::cast_ray_edges(ray, self.edges(), max_toi, solid, self.contains(ray.origin))
+    }
+
+    #[cfg(feature = "debug-draw")]
+    fn debug_geometry(&self, b: &mut Builder) {
+        self.add_geometry(b);
     }
 }
 
 impl Collides<Capsule> for Polygon {
     fn collide(&self, other: &Capsule) -> Option<ContactManifold> {
-        (self.contains(other.line.start)
-            || self.contains(other.line.end)
-            || self.edges().any(|edge| edge.collide(other).colliding))
-        .into()
+        algorithms::collide_capsule_polygon(other, self).map(|manifold| -manifold)
     }
 }
 
 impl Collides<Circle> for Polygon {
     fn collide(&self, other: &Circle) -> Option<ContactManifold> {
-        (self.contains(other.position) || self.edges().any(|edge| edge.collide(other).colliding))
-            .into()
+        algorithms::collide_circle_polygon(other, self).map(|manifold| -manifold)
+    }
+}
+
+impl Collides<ConvexPolygon> for Polygon {
+    fn collide(&self, other: &ConvexPolygon) -> Option<ContactManifold> {
+        algorithms::collide_convex_polygon_polygon(other, self).map(|manifold| -manifold)
     }
 }
 
 impl Collides<Line> for Polygon {
     fn collide(&self, other: &Line) -> Option<ContactManifold> {
-        (self.contains(other.start)
-            || self.contains(other.end)
-            || self.edges().any(|edge| edge.collide(other).colliding))
-        .into()
+        algorithms::collide_line_polygon(other, self).map(|manifold| -manifold)
     }
 }
 
 impl Collides<Point> for Polygon {
     fn collide(&self, other: &Point) -> Option<ContactManifold> {
-        self.contains(other.0).into()
+        algorithms::collide_point_polygon(other, self).map(|manifold| -manifold)
     }
 }
 
 impl Collides<Polygon> for Polygon {
     fn collide(&self, other: &Polygon) -> Option<ContactManifold> {
-        (self.vertices.iter().any(|&vertex| other.contains(vertex))
-            || other.vertices.iter().any(|&vertex| self.contains(vertex))
-            || self.edges().any(|edge| edge.collide(other).colliding))
-        .into()
+        algorithms::collide_polygon_polygon(self, other)
     }
 }
 
 impl Collides<Rectangle> for Polygon {
     fn collide(&self, other: &Rectangle) -> Option<ContactManifold> {
-        (self.contains(other.min())
-            || self.contains(other.max())
-            || self.edges().any(|edge| edge.collide(other).colliding))
-        .into()
+        algorithms::collide_polygon_rect(self, other)
     }
 }
 
@@ -165,6 +339,34 @@ impl Geometry for Polygon {
     }
 }
 
+/// The feature of the closed outline formed by `vertices` that `point` lies on, see [`Shape::feature_at`].
+pub(crate) fn outline_feature(vertices: &[Vec2], point: Vec2) -> FeatureId {
+    let len = vertices.len();
+    EdgesIterator::new(vertices)
+        .enumerate()
+        .min_by(|(_, a), (_, b)| {
+            a.distance_to_point_squared(&point)
+                .total_cmp(&b.distance_to_point_squared(&point))
+        })
+        .map_or(FeatureId::Unknown, |(index, edge)| {
+            segment_feature(&edge, index, (index + 1) % len, point)
+        })
+}
+
+/// The feature of `edge` that `point` lies on, where `start` and `end` are the indices of its vertices and
+/// `start` is also the index of the edge itself.
+pub(crate) fn segment_feature(edge: &Line, start: usize, end: usize, point: Vec2) -> FeatureId {
+    // Points within a small fraction of the edge's length from either end count as being on that vertex
+    let tolerance = 1e-3 * edge.length().max(1.0);
+    if edge.start.distance_squared(point) <= tolerance * tolerance {
+        FeatureId::Vertex(start as u32)
+    } else if edge.end.distance_squared(point) <= tolerance * tolerance {
+        FeatureId::Vertex(end as u32)
+    } else {
+        FeatureId::Edge(start as u32)
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct EdgesIterator<'a> {
     vertices: &'a [Vec2],
@@ -212,3 +414,30 @@ impl Iterator for EdgesIterator<'_> {
 impl ExactSizeIterator for EdgesIterator<'_> {}
 
 impl FusedIterator for EdgesIterator<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transforming_keeps_the_convex_part() {
+        let transform = GlobalTransform::from(
+            Transform::from_xyz(5.0, 0.0, 0.0).with_rotation(Quat::from_rotation_z(1.0)),
+        );
+        let square = Polygon::from(ConvexPolygon::from(Rectangle::new(0.0, 0.0, 2.0, 2.0)));
+        let transformed = square.to_transformed(&transform);
+
+        let convex = transformed.as_convex().unwrap();
+        assert_eq!(convex.vertices(), transformed.vertices);
+        assert!(convex.contains(transform.transform_point2(Vec2::new(1.0, 1.0))));
+        assert!(!convex.contains(transform.transform_point2(Vec2::new(3.0, 1.0))));
+
+        let concave = Polygon::new(vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(2.0, 0.0),
+            Vec2::new(1.0, 0.5),
+            Vec2::new(1.0, 2.0),
+        ]);
+        assert!(!concave.to_transformed(&transform).is_convex());
+    }
+}
@@ -0,0 +1,223 @@
+use super::*;
+use crate::transform_ext::TransformPoint2;
+
+/// A ray that starts at `origin` and extends infinitely far along `direction`.
+///
+/// `direction` doesn't need to be normalized; times of impact are measured in multiples of its length.
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub struct Ray2d {
+    pub origin: Vec2,
+    pub direction: Vec2,
+}
+
+impl Ray2d {
+    pub fn new(origin: Vec2, direction: Vec2) -> Self {
+        Self { origin, direction }
+    }
+
+    /// A ray from `start` towards `end`, which reaches `end` at a time of impact of 1.
+    pub fn between(start: Vec2, end: Vec2) -> Self {
+        Self::new(start, end - start)
+    }
+
+    /// The point that `self` reaches at the given time of impact.
+    pub fn point_at(&self, toi: f32) -> Vec2 {
+        self.origin + (self.direction * toi)
+    }
+}
+
+impl Transformable for Ray2d {
+    fn to_transformed(&self, transform: &GlobalTransform) -> Self {
+        Self::new(
+            transform.transform_point2(self.origin),
+            transform.transform_vec2(self.direction),
+        )
+    }
+}
+
+/// Where a ray hits a collider, see [`Collider::cast_ray`].
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub struct RayHit {
+    /// The time of impact, such that `point` is [`Ray2d::point_at`] this time.
+    pub toi: f32,
+    pub point: Vec2,
+    /// The unit normal of the surface that was hit, facing back towards the origin of the ray.
+    ///
+    /// This is zero for solid hits where the ray starts inside the collider.
+    pub normal: Vec2,
+}
+
+impl RayHit {
+    pub(crate) fn new(ray: &Ray2d, toi: f32, normal: Vec2) -> Self {
+        let normal = normal.normalize_or_zero();
+        Self {
+            toi,
+            point: ray.point_at(toi),
+            normal: if normal.dot(ray.direction) > 0.0 {
+                -normal
+            } else {
+                normal
+            },
+        }
+    }
+
+    /// A hit at the very start of a ray, for solid shapes that the ray starts inside of.
+    pub(crate) fn inside(ray: &Ray2d) -> Self {
+        Self {
+            toi: 0.0,
+            point: ray.origin,
+            normal: Vec2::ZERO,
+        }
+    }
+}
+
+/// The time at which `ray` crosses `line`, if it does.
+///
+/// When they are colinear, this is the first time at which `ray` touches `line`.
+pub(crate) fn line_toi(ray: &Ray2d, line: &Line) -> Option<f32> {
+    let edge = line.as_difference();
+    let to_start = line.start - ray.origin;
+    let denominator = ray.direction.perp_dot(edge);
+
+    if denominator.abs() <= f32::EPSILON {
+        if to_start.perp_dot(ray.direction).abs() > f32::EPSILON {
+            return None;
+        }
+
+        // Colinear, so the ray touches the line at whichever end it reaches first, unless it starts on it
+        let length_squared = ray.direction.length_squared();
+        if length_squared <= f32::EPSILON {
+            return None;
+        }
+
+        let start = to_start.dot(ray.direction) / length_squared;
+        let end = (line.end - ray.origin).dot(ray.direction) / length_squared;
+        let (first, last) = (start.min(end), start.max(end));
+        return (last >= 0.0).then_some(first.max(0.0));
+    }
+
+    let toi = to_start.perp_dot(edge) / denominator;
+    let t = to_start.perp_dot(ray.direction) / denominator;
+    (toi >= 0.0 && (0.0..=1.0).contains(&t)).then_some(toi)
+}
+
+/// The times at which `ray` enters and leaves the circle with the given center and radius, which may be negative.
+pub(crate) fn circle_tois(ray: &Ray2d, center: Vec2, radius: f32) -> Option<(f32, f32)> {
+    let offset = ray.origin - center;
+    let a = ray.direction.length_squared();
+    let b = offset.dot(ray.direction);
+    let c = offset.length_squared() - (radius * radius);
+
+    if a <= f32::EPSILON {
+        return None;
+    }
+
+    let discriminant = (b * b) - (a * c);
+    if discriminant < 0.0 {
+        return None;
+    }
+
+    let root = discriminant.sqrt();
+    Some(((-b - root) / a, (-b + root) / a))
+}
+
+/// The first crossing of `ray` with any of `edges`, where `contains_origin` is whether the shape they outline
+/// contains the origin of the ray.
+pub(crate) fn cast_ray_edges(
+    ray: &Ray2d,
+    edges: impl IntoIterator<Item = Line>,
+    max_toi: f32,
+    solid: bool,
+    contains_origin: bool,
+) -> Option<RayHit> {
+    if solid && contains_origin {
+        return Some(RayHit::inside(ray));
+    }
+
+    edges
+        .into_iter()
+        .filter_map(|edge| line_toi(ray, &edge).map(|toi| (toi, edge)))
+        .filter(|(toi, _)| *toi <= max_toi)
+        .min_by(|(a, _), (b, _)| a.total_cmp(b))
+        .map(|(toi, edge)| RayHit::new(ray, toi, edge.perp()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn colliders() -> Vec<Collider> {
+        vec![
+            Collider::Capsule(Capsule::new(
+                Line::new(Vec2::new(0.0, -1.0), Vec2::new(0.0, 1.0)),
+                2.0,
+            )),
+            Collider::Circle(Circle::new(Vec2::ZERO, 2.0)),
+            Collider::ConvexPolygon(ConvexPolygon::from(Rectangle::new(-2.0, -2.0, 2.0, 2.0))),
+            Collider::Polygon(Polygon::new(vec![
+                Vec2::new(-2.0, -2.0),
+                Vec2::new(2.0, -2.0),
+                Vec2::new(2.0, 2.0),
+                Vec2::new(0.0, 1.0),
+                Vec2::new(-2.0, 2.0),
+            ])),
+            Collider::Rectangle(Rectangle::new(-2.0, -2.0, 2.0, 2.0)),
+        ]
+    }
+
+    #[test]
+    fn hits_from_outside() {
+        let ray = Ray2d::new(Vec2::new(-10.0, 0.0), Vec2::X);
+
+        for collider in colliders() {
+            let hit = collider
+                .cast_ray(&GlobalTransform::IDENTITY, &ray, f32::INFINITY, true)
+                .unwrap();
+            assert!((hit.toi - 8.0).abs() < 1e-4, "{collider:?}");
+            assert!(hit.point.abs_diff_eq(Vec2::new(-2.0, 0.0), 1e-4));
+            assert!(hit.normal.abs_diff_eq(Vec2::NEG_X, 1e-4));
+
+            assert!(collider
+                .cast_ray(&GlobalTransform::IDENTITY, &ray, 7.5, true)
+                .is_none());
+        }
+    }
+
+    #[test]
+    fn rays_starting_inside_hit_on_the_way_out() {
+        let ray = Ray2d::new(Vec2::new(-1.0, 0.0), Vec2::X);
+
+        for collider in colliders() {
+            let solid = collider
+                .cast_ray(&GlobalTransform::IDENTITY, &ray, f32::INFINITY, true)
+                .unwrap();
+            assert_eq!(solid.toi, 0.0);
+            assert_eq!(solid.normal, Vec2::ZERO);
+
+            let hollow = collider
+                .cast_ray(&GlobalTransform::IDENTITY, &ray, f32::INFINITY, false)
+                .unwrap();
+            assert!((hollow.toi - 3.0).abs() < 1e-4, "{collider:?}");
+            assert!(hollow.point.abs_diff_eq(Vec2::new(2.0, 0.0), 1e-4));
+            assert!(hollow.normal.abs_diff_eq(Vec2::NEG_X, 1e-4));
+        }
+    }
+
+    #[test]
+    fn rays_leaving_a_concave_polygon() {
+        let polygon = Collider::Polygon(Polygon::new(vec![
+            Vec2::new(-2.0, -2.0),
+            Vec2::new(2.0, -2.0),
+            Vec2::new(2.0, 2.0),
+            Vec2::new(0.0, 1.0),
+            Vec2::new(-2.0, 2.0),
+        ]));
+
+        // Straight up out of the notch at the top
+        let ray = Ray2d::new(Vec2::ZERO, Vec2::Y);
+        let hit = polygon
+            .cast_ray(&GlobalTransform::IDENTITY, &ray, f32::INFINITY, false)
+            .unwrap();
+        assert!((hit.toi - 1.0).abs() < 1e-4);
+    }
+}
@@ -49,6 +49,11 @@ impl Rectangle {
         point.clamp(self.min(), self.max())
     }
 
+    /// How far the given point is from the closest point within the bounds of `self`, which is 0 inside.
+    pub fn distance_to_point(&self, point: Vec2) -> f32 {
+        self.closest_point(point).distance(point)
+    }
+
     /// The closest point on the perimeter of `self` to the given point, as well as a boolean indicating
     /// whether the given point lies inside `self` or not.
     pub fn closest_point_on_perimeter(&self, point: Vec2) -> (Vec2, bool) {
@@ -162,6 +167,11 @@ impl Rectangle {
         Self(self.0.intersect(other.0))
     }
 
+    /// Whether or not `self` and `other` overlap, including when they only share an edge or corner.
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.min().cmple(other.max()).all() && other.min().cmple(self.max()).all()
+    }
+
     /// See [`Rect::inset`]
     #[inline]
     pub fn inset(&self, inset: f32) -> Self {
@@ -184,33 +194,92 @@ impl Transformable for Rectangle {
     }
 }
 
+impl Shape for Rectangle {
+    fn support_point(&self, direction: Vec2) -> Vec2 {
+        Vec2::new(
+            if direction.x >= 0.0 {
+                self.max().x
+            } else {
+                self.min().x
+            },
+            if direction.y >= 0.0 {
+                self.max().y
+            } else {
+                self.min().y
+            },
+        )
+    }
+
+    fn aabb(&self) -> Rectangle {
+        *self
+    }
+
+    fn contains(&self, point: Vec2) -> bool {
+        Rectangle::contains(self, point)
+    }
+
+    /// Features are numbered anticlockwise from the bottom left corner, like a [`ConvexPolygon`] made from `self`.
+    fn feature_at(&self, point: Vec2) -> FeatureId {
+        polygon::outline_feature(
+            &[
+                self.min(),
+                self.bottom_right_corner(),
+                self.max(),
+                self.top_left_corner(),
+            ],
+            point,
+        )
+    }
+
+    fn cast_ray(&self, ray: &Ray2d, max_toi: f32, solid: bool) -> Option<RayHit> {
+        ray::cast_ray_edges(
+            ray,
+            [self.bottom(), self.right(), self.top(), self.left()],
+            max_toi,
+            solid,
+            self.contains(ray.origin),
+        )
+    }
+
+    #[cfg(feature = "debug-draw")]
+    fn debug_geometry(&self, b: &mut Builder) {
+        self.add_geometry(b);
+    }
+}
+
 impl Collides<Capsule> for Rectangle {
     fn collide(&self, other: &Capsule) -> Option<ContactManifold> {
-        other.collide(self)
+        algorithms::collide_capsule_rect(other, self).map(|manifold| -manifold)
     }
 }
 
 impl Collides<Circle> for Rectangle {
     fn collide(&self, other: &Circle) -> Option<ContactManifold> {
-        algorithms::collide_circle_rect(other, self).neg()
+        algorithms::collide_circle_rect(other, self).map(|manifold| -manifold)
+    }
+}
+
+impl Collides<ConvexPolygon> for Rectangle {
+    fn collide(&self, other: &ConvexPolygon) -> Option<ContactManifold> {
+        other.collide(self).map(|manifold| -manifold)
     }
 }
 
 impl Collides<Line> for Rectangle {
     fn collide(&self, other: &Line) -> Option<ContactManifold> {
-        other.collide(self)
+        algorithms::collide_line_rect(other, self).map(|manifold| -manifold)
     }
 }
 
 impl Collides<Point> for Rectangle {
     fn collide(&self, other: &Point) -> Option<ContactManifold> {
-        algorithms::collide_point_rect(other, self).neg()
+        algorithms::collide_point_rect(other, self).map(|manifold| -manifold)
     }
 }
 
 impl Collides<Polygon> for Rectangle {
     fn collide(&self, other: &Polygon) -> Option<ContactManifold> {
-        other.collide(self)
+        algorithms::collide_polygon_rect(other, self).map(|manifold| -manifold)
     }
 }
 
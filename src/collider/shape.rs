@@ -0,0 +1,138 @@
+use super::*;
+use crate::transform_ext::TransformPoint2;
+use bevy::math::{Affine2, Mat2};
+use std::fmt::Debug;
+
+/// A user-defined collider shape, for use with [`Collider::Custom`].
+///
+/// Collisions between custom shapes and any other collider are detected with a generic narrowphase (GJK + EPA)
+/// that only relies on [`Shape::support_point`], which means that custom shapes are always treated as convex.
+pub trait Shape: Debug + Send + Sync + 'static {
+    /// The point on `self` that is furthest along `direction`.
+    ///
+    /// `direction` is not necessarily normalized.
+    fn support_point(&self, direction: Vec2) -> Vec2;
+
+    /// The smallest axis-aligned rectangle that contains `self`.
+    fn aabb(&self) -> Rectangle {
+        Rectangle::new(
+            self.support_point(Vec2::NEG_X).x,
+            self.support_point(Vec2::NEG_Y).y,
+            self.support_point(Vec2::X).x,
+            self.support_point(Vec2::Y).y,
+        )
+    }
+
+    /// Whether or not `self` contains the given point.
+    fn contains(&self, point: Vec2) -> bool;
+
+    /// The vertex or edge of `self` that the given point on its outline lies on, see [`FeatureId`].
+    ///
+    /// Used to give contacts stable IDs. The default implementation returns [`FeatureId::Unknown`].
+    fn feature_at(&self, point: Vec2) -> FeatureId {
+        let _ = point;
+        FeatureId::Unknown
+    }
+
+    /// The first point at which `ray` hits the outline of `self`, within `max_toi`.
+    ///
+    /// If `ray` starts inside `self`, a `solid` shape is hit immediately, and otherwise the ray hits the outline
+    /// on its way out. The default implementation casts against the convex hull of `self` using
+    /// [`gjk::cast_ray`].
+    fn cast_ray(&self, ray: &Ray2d, max_toi: f32, solid: bool) -> Option<RayHit> {
+        gjk::cast_ray(self, ray, max_toi, solid)
+    }
+
+    /// Adds the outline of `self` to the given path builder, for debug drawing.
+    ///
+    /// The default implementation traces the outline of `self` by sampling [`Shape::support_point`].
+    #[cfg(feature = "debug-draw")]
+    fn debug_geometry(&self, b: &mut Builder) {
+        const SAMPLES: usize = 32;
+
+        let mut points = (0..SAMPLES).map(|i| {
+            let angle = (i as f32 / SAMPLES as f32) * std::f32::consts::TAU;
+            self.support_point(Vec2::from_angle(angle))
+        });
+
+        let Some(first) = points.next() else { return; };
+
+        b.begin((first.x, first.y).into());
+        for point in points {
+            b.line_to((point.x, point.y).into());
+        }
+        b.end(true);
+    }
+}
+
+/// Custom shapes are compared by identity, as there is no general way to compare their contents.
+impl PartialEq for dyn Shape {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(
+            self as *const dyn Shape as *const u8,
+            other as *const dyn Shape as *const u8,
+        )
+    }
+}
+
+impl Transformable for Arc<dyn Shape> {
+    fn to_transformed(&self, transform: &GlobalTransform) -> Self {
+        Arc::new(TransformedShape::new(self.clone(), transform))
+    }
+}
+
+/// A custom shape that has been moved into world space, see [`Transformable`].
+#[derive(Clone, Debug)]
+pub struct TransformedShape {
+    pub shape: Arc<dyn Shape>,
+    affine: Affine2,
+    inverse: Affine2,
+}
+
+impl TransformedShape {
+    pub fn new(shape: Arc<dyn Shape>, transform: &GlobalTransform) -> Self {
+        let affine = Affine2::from_mat2_translation(
+            Mat2::from_cols(
+                transform.transform_vec2(Vec2::X),
+                transform.transform_vec2(Vec2::Y),
+            ),
+            transform.transform_point2(Vec2::ZERO),
+        );
+
+        Self {
+            shape,
+            affine,
+            inverse: affine.inverse(),
+        }
+    }
+}
+
+impl Shape for TransformedShape {
+    fn support_point(&self, direction: Vec2) -> Vec2 {
+        // Directions are transformed by the transpose of the linear part, so that dot products are preserved
+        let local_direction = self.affine.matrix2.transpose() * direction;
+        self.affine
+            .transform_point2(self.shape.support_point(local_direction))
+    }
+
+    fn contains(&self, point: Vec2) -> bool {
+        self.shape.contains(self.inverse.transform_point2(point))
+    }
+
+    fn feature_at(&self, point: Vec2) -> FeatureId {
+        self.shape.feature_at(self.inverse.transform_point2(point))
+    }
+
+    fn cast_ray(&self, ray: &Ray2d, max_toi: f32, solid: bool) -> Option<RayHit> {
+        // Affine transforms preserve times of impact, so only the hit point and normal need transforming back
+        let local_ray = Ray2d::new(
+            self.inverse.transform_point2(ray.origin),
+            self.inverse.transform_vector2(ray.direction),
+        );
+
+        self.shape.cast_ray(&local_ray, max_toi, solid).map(|hit| {
+            let normal = self.inverse.matrix2.transpose() * hit.normal;
+            RayHit::new(ray, hit.toi, normal)
+        })
+    }
+}
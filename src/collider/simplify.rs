@@ -0,0 +1,365 @@
+use super::*;
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+/// Simplifies an open chain of points using the Douglas-Peucker algorithm.
+///
+/// Points that lie within `tolerance` of the simplified chain are removed. The first and last points are always
+/// kept, and if the input chain is simple (non self-intersecting) then so is the output.
+pub fn douglas_peucker(points: &[Vec2], tolerance: f32) -> Vec<Vec2> {
+    if points.len() <= 2 {
+        return points.to_vec();
+    }
+
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+
+    douglas_peucker_span(points, 0, points.len() - 1, tolerance, &mut keep);
+    repair_self_intersections(points, &mut keep, false);
+
+    collect_kept(points, &keep)
+}
+
+/// Simplifies a closed polygon outline using the Douglas-Peucker algorithm.
+///
+/// See [`douglas_peucker`]. At least 3 vertices are always kept.
+pub fn douglas_peucker_closed(points: &[Vec2], tolerance: f32) -> Vec<Vec2> {
+    if points.len() <= 3 {
+        return points.to_vec();
+    }
+
+    // Anchor the outline on the first vertex and the vertex furthest from it, and simplify each half separately
+    let len = points.len();
+    let far = (1..len)
+        .max_by(|&a, &b| {
+            points[0]
+                .distance_squared(points[a])
+                .total_cmp(&points[0].distance_squared(points[b]))
+        })
+        .unwrap_or(1);
+
+    let mut keep = vec![false; len];
+    keep[0] = true;
+    keep[far] = true;
+
+    douglas_peucker_span(points, 0, far, tolerance, &mut keep);
+    douglas_peucker_span(points, far, len, tolerance, &mut keep);
+
+    if keep.iter().filter(|&&kept| kept).count() < 3 {
+        // Everything collapsed onto the anchor line, so keep the vertex furthest from it to retain some area
+        let furthest = furthest_from_span(points, 0, far)
+            .into_iter()
+            .chain(furthest_from_span(points, far, len))
+            .max_by(|(_, a), (_, b)| a.total_cmp(b));
+
+        if let Some((index, _)) = furthest {
+            keep[index % len] = true;
+        }
+    }
+
+    repair_self_intersections(points, &mut keep, true);
+
+    collect_kept(points, &keep)
+}
+
+/// Simplifies an open chain of points using the Visvalingam-Whyatt algorithm.
+///
+/// Points are removed in order of the area of the triangle they form with their neighbours, until every
+/// remaining point forms a triangle with an area of at least `tolerance`. The first and last points are always
+/// kept, and points whose removal would make the chain self-intersect are skipped.
+pub fn visvalingam_whyatt(points: &[Vec2], tolerance: f32) -> Vec<Vec2> {
+    visvalingam_whyatt_impl(points, tolerance, false)
+}
+
+/// Simplifies a closed polygon outline using the Visvalingam-Whyatt algorithm.
+///
+/// See [`visvalingam_whyatt`]. At least 3 vertices are always kept.
+pub fn visvalingam_whyatt_closed(points: &[Vec2], tolerance: f32) -> Vec<Vec2> {
+    visvalingam_whyatt_impl(points, tolerance, true)
+}
+
+/// Checks whether the closed outline formed by `vertices` has any pair of non-adjacent edges that intersect.
+pub(crate) fn is_simple_outline(vertices: &[Vec2]) -> bool {
+    let edges: Vec<Line> = polygon::EdgesIterator::new(vertices).collect();
+    let len = edges.len();
+
+    for i in 0..len {
+        for j in (i + 1)..len {
+            let adjacent = j == i + 1 || (i == 0 && j == len - 1);
+            if !adjacent && edges[i].intersects_line(&edges[j]) {
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+/// Marks the points of `points[start..end]` that are needed to keep the chain within `tolerance`.
+///
+/// Indices are taken modulo `points.len()`, so that `end` can wrap around to the start of a closed outline.
+fn douglas_peucker_span(
+    points: &[Vec2],
+    start: usize,
+    end: usize,
+    tolerance: f32,
+    keep: &mut [bool],
+) {
+    let mut stack = vec![(start, end)];
+    while let Some((start, end)) = stack.pop() {
+        let Some((index, distance)) = furthest_from_span(points, start, end) else {
+            continue;
+        };
+
+        if distance > tolerance {
+            keep[index % points.len()] = true;
+            stack.push((start, index));
+            stack.push((index, end));
+        }
+    }
+}
+
+/// The index of the point strictly between `start` and `end` that is furthest from the line between them, along
+/// with that distance.
+fn furthest_from_span(points: &[Vec2], start: usize, end: usize) -> Option<(usize, f32)> {
+    let line = Line::new(points[start % points.len()], points[end % points.len()]);
+    ((start + 1)..end)
+        .map(|index| (index, line.distance_to_point(&points[index % points.len()])))
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+}
+
+/// Re-inserts removed points until no two simplified segments intersect each other.
+///
+/// Each offending segment is split at the removed point that is furthest from it, which always terminates
+/// because segments that span no removed points are never split.
+fn repair_self_intersections(points: &[Vec2], keep: &mut [bool], closed: bool) {
+    let len = points.len();
+    loop {
+        let kept: Vec<usize> = (0..len).filter(|&index| keep[index]).collect();
+        let mut spans: Vec<(usize, usize)> = kept.windows(2).map(|w| (w[0], w[1])).collect();
+        if closed {
+            spans.push((kept[kept.len() - 1], kept[0] + len));
+        }
+
+        let segments: Vec<Line> = spans
+            .iter()
+            .map(|&(start, end)| Line::new(points[start % len], points[end % len]))
+            .collect();
+
+        let mut changed = false;
+        for (i, &(start, end)) in spans.iter().enumerate() {
+            if end - start < 2 {
+                continue;
+            }
+
+            let intersects = segments.iter().enumerate().any(|(j, other)| {
+                let adjacent = i.abs_diff(j) <= 1 || (closed && i.abs_diff(j) == spans.len() - 1);
+                !adjacent && segments[i].intersects_line(other)
+            });
+
+            if intersects {
+                if let Some((index, _)) = furthest_from_span(points, start, end) {
+                    keep[index % len] = true;
+                    changed = true;
+                }
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+}
+
+fn collect_kept(points: &[Vec2], keep: &[bool]) -> Vec<Vec2> {
+    points
+        .iter()
+        .zip(keep)
+        .filter_map(|(&point, &kept)| kept.then_some(point))
+        .collect()
+}
+
+/// A candidate for removal, ordered so that the smallest area is popped from the heap first.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Candidate {
+    area: f32,
+    index: usize,
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .area
+            .total_cmp(&self.area)
+            .then_with(|| other.index.cmp(&self.index))
+    }
+}
+
+fn visvalingam_whyatt_impl(points: &[Vec2], tolerance: f32, closed: bool) -> Vec<Vec2> {
+    let len = points.len();
+    let min_len = if closed { 3 } else { 2 };
+    if len <= min_len {
+        return points.to_vec();
+    }
+
+    // A doubly linked list over the remaining points
+    let mut prev: Vec<usize> = (0..len).map(|i| (i + len - 1) % len).collect();
+    let mut next: Vec<usize> = (0..len).map(|i| (i + 1) % len).collect();
+    let mut removed = vec![false; len];
+    let mut areas = vec![f32::INFINITY; len];
+    let mut remaining = len;
+
+    let is_endpoint = |index: usize| !closed && (index == 0 || index == len - 1);
+    let triangle_area = |prev: usize, index: usize, next: usize| {
+        Triangle::new(points[prev], points[index], points[next]).area()
+    };
+
+    let mut heap = BinaryHeap::new();
+    for index in (0..len).filter(|&index| !is_endpoint(index)) {
+        areas[index] = triangle_area(prev[index], index, next[index]);
+        heap.push(Candidate {
+            area: areas[index],
+            index,
+        });
+    }
+
+    while let Some(Candidate { area, index }) = heap.pop() {
+        if removed[index] || area != areas[index] {
+            // A stale entry, superseded by a later push
+            continue;
+        }
+
+        if area >= tolerance || remaining <= min_len {
+            break;
+        }
+
+        // Skip removals that would make the outline self-intersect; the point will be reconsidered once one of
+        // its neighbours is removed
+        let (before, after) = (prev[index], next[index]);
+        let shortcut = Line::new(points[before], points[after]);
+        let start = if closed { after } else { 0 };
+        let mut current = start;
+        let mut blocked = false;
+        loop {
+            if !closed && current == len - 1 {
+                break;
+            }
+
+            let following = next[current];
+            let shares_endpoint =
+                current == before || current == index || current == after || following == before;
+            if !shares_endpoint
+                && shortcut.intersects_line(&Line::new(points[current], points[following]))
+            {
+                blocked = true;
+                break;
+            }
+
+            current = following;
+            if current == start {
+                break;
+            }
+        }
+
+        if blocked {
+            areas[index] = f32::INFINITY;
+            continue;
+        }
+
+        removed[index] = true;
+        remaining -= 1;
+
+        next[before] = after;
+        prev[after] = before;
+
+        // Neighbours can never be removed before the point that was just removed
+        for neighbour in [before, after] {
+            if !is_endpoint(neighbour) {
+                areas[neighbour] =
+                    triangle_area(prev[neighbour], neighbour, next[neighbour]).max(area);
+                heap.push(Candidate {
+                    area: areas[neighbour],
+                    index: neighbour,
+                });
+            }
+        }
+    }
+
+    points
+        .iter()
+        .zip(&removed)
+        .filter_map(|(&point, &removed)| (!removed).then_some(point))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A simple polygon with a peak that lies within 1 of its base, above a spike reaching up from below it.
+    fn peak_over_spike() -> Polygon {
+        Polygon::new(vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(5.0, 1.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(10.0, -3.0),
+            Vec2::new(7.0, -3.0),
+            Vec2::new(5.0, 0.5),
+            Vec2::new(3.0, -3.0),
+            Vec2::new(0.0, -3.0),
+        ])
+    }
+
+    #[test]
+    fn douglas_peucker_removes_nearly_straight_points() {
+        let points = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(1.0, 0.1),
+            Vec2::new(2.0, -0.1),
+            Vec2::new(3.0, 0.0),
+        ];
+
+        assert_eq!(douglas_peucker(&points, 0.5), vec![points[0], points[3]]);
+        assert_eq!(douglas_peucker(&points, 0.05), points.to_vec());
+    }
+
+    #[test]
+    fn visvalingam_whyatt_removes_small_triangles() {
+        let points = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(1.0, 0.9),
+            Vec2::new(2.0, 2.0),
+            Vec2::new(3.0, 0.0),
+        ];
+
+        assert_eq!(
+            visvalingam_whyatt(&points, 0.5),
+            vec![points[0], points[2], points[3]]
+        );
+    }
+
+    #[test]
+    fn simplified_outlines_stay_simple() {
+        let polygon = peak_over_spike();
+        assert!(polygon.is_simple());
+
+        // Dropping the peak alone would cut straight through the spike
+        let simplified = polygon.simplify_douglas_peucker(1.2);
+        assert!(simplified.is_simple());
+        assert!(simplified.vertices.len() >= 3);
+
+        for tolerance in [1.0, 3.0, 5.5, 8.0] {
+            let simplified = polygon.simplify_visvalingam_whyatt(tolerance);
+            assert!(simplified.is_simple());
+            assert!(simplified.vertices.len() >= 3);
+        }
+    }
+}
@@ -0,0 +1,298 @@
+use super::*;
+
+const MAX_ITERATIONS: usize = 32;
+
+/// How close a pair of swept colliders need to get to count as touching.
+const TOLERANCE: f32 = 1e-3;
+
+/// The motion of a collider from one transform to another over the course of a frame.
+///
+/// In between, the translation and scale are interpolated linearly, and the rotation spherically.
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub struct Sweep {
+    pub start: GlobalTransform,
+    pub end: GlobalTransform,
+}
+
+impl Sweep {
+    pub fn new(start: GlobalTransform, end: GlobalTransform) -> Self {
+        Self { start, end }
+    }
+
+    /// A collider that doesn't move.
+    pub fn stationary(transform: GlobalTransform) -> Self {
+        Self::new(transform, transform)
+    }
+
+    /// The transform at time `t`, where 0 is the start of the sweep and 1 is the end.
+    pub fn at(&self, t: f32) -> GlobalTransform {
+        let start = self.start.compute_transform();
+        let end = self.end.compute_transform();
+        Transform {
+            translation: start.translation.lerp(end.translation, t),
+            rotation: start.rotation.slerp(end.rotation, t),
+            scale: start.scale.lerp(end.scale, t),
+        }
+        .into()
+    }
+
+    /// An upper bound on how far any point of `collider` moves over the whole sweep.
+    fn max_displacement(&self, collider: &Collider) -> f32 {
+        let (translation, turning) = self.displacement(collider);
+        translation + turning
+    }
+
+    /// A bounding box that contains `collider` throughout the sweep.
+    fn aabb(&self, collider: &Collider) -> Rectangle {
+        let start = collider.to_transformed(&self.start).aabb();
+        let end = collider.to_transformed(&self.end).aabb();
+
+        // Rotating and scaling can carry points beyond the boxes at either end
+        let (_, turning) = self.displacement(collider);
+        start.union(end).inset(turning)
+    }
+
+    /// Upper bounds on how far any point of `collider` moves due to the translation, and due to the rotation and
+    /// scale, over the whole sweep.
+    fn displacement(&self, collider: &Collider) -> (f32, f32) {
+        let start = self.start.compute_transform();
+        let end = self.end.compute_transform();
+
+        let furthest_corner = |rect: Rectangle, center: Vec2| {
+            [
+                rect.min(),
+                rect.max(),
+                rect.top_left_corner(),
+                rect.bottom_right_corner(),
+            ]
+            .into_iter()
+            .map(|corner| corner.distance(center))
+            .fold(0.0, f32::max)
+        };
+
+        let local_radius = furthest_corner(collider.aabb(), Vec2::ZERO);
+        let radius = [self.start, self.end]
+            .into_iter()
+            .map(|transform| {
+                let center = transform.translation().truncate();
+                furthest_corner(collider.to_transformed(&transform).aabb(), center)
+            })
+            .fold(0.0, f32::max);
+
+        let translation = start
+            .translation
+            .truncate()
+            .distance(end.translation.truncate());
+        let rotation = start.rotation.angle_between(end.rotation) * radius;
+        let scale = (end.scale - start.scale).truncate().abs().max_element() * local_radius;
+
+        (translation, rotation + scale)
+    }
+}
+
+/// The first point in time at which a pair of swept colliders touch, see [`Collider::time_of_impact`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TimeOfImpact {
+    /// The time of the first contact, from 0 at the start of the sweeps to 1 at the end.
+    pub time: f32,
+    /// The contact between the colliders at that time.
+    pub contact: ContactManifold,
+}
+
+/// Where a moving collider first hits another, see [`Collider::cast_shape`].
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub struct ShapeHit {
+    /// The time of impact, as a fraction of the velocity that the collider was cast with.
+    pub toi: f32,
+    /// The point on the moving collider that touches `them`, at the time of impact.
+    pub us: Vec2,
+    /// The point on the other collider that touches `us`.
+    pub them: Vec2,
+    /// The unit normal pointing from the moving collider towards the other collider.
+    pub normal: Vec2,
+}
+
+/// Casts `us` along `velocity` until it hits `them`, see [`Collider::cast_shape`].
+pub(crate) fn cast_shape(
+    us: &Collider,
+    transform: &GlobalTransform,
+    velocity: Vec2,
+    them: &Collider,
+    them_transform: &GlobalTransform,
+) -> Option<ShapeHit> {
+    let end = transform.compute_transform();
+    let end = Transform {
+        translation: end.translation + velocity.extend(0.0),
+        ..end
+    };
+
+    let time_of_impact = time_of_impact(
+        us,
+        &Sweep::new(*transform, end.into()),
+        them,
+        &Sweep::stationary(*them_transform),
+    )?;
+
+    let contact = time_of_impact
+        .contact
+        .contact_points()
+        .max_by(|a, b| a.depth().total_cmp(&b.depth()))?;
+
+    Some(ShapeHit {
+        toi: time_of_impact.time,
+        us: contact.us,
+        them: contact.them,
+        normal: contact.normal,
+    })
+}
+
+/// Finds the time of impact of a pair of swept colliders using conservative advancement.
+///
+/// The colliders are repeatedly advanced by the largest step that can't possibly make them overlap, based on
+/// the distance between them and how fast their points can move, until they are touching.
+pub(crate) fn time_of_impact(
+    us: &Collider,
+    us_sweep: &Sweep,
+    them: &Collider,
+    them_sweep: &Sweep,
+) -> Option<TimeOfImpact> {
+    if distance::aabbs_are_further_than(&us_sweep.aabb(us), &them_sweep.aabb(them), TOLERANCE) {
+        return None;
+    }
+
+    let max_displacement = us_sweep.max_displacement(us) + them_sweep.max_displacement(them);
+
+    let mut time = 0.0;
+    for _ in 0..MAX_ITERATIONS {
+        let us_world = us.to_transformed(&us_sweep.at(time));
+        let them_world = them.to_transformed(&them_sweep.at(time));
+
+        if let Some(contact) = us_world.collide(&them_world) {
+            return Some(TimeOfImpact { time, contact });
+        }
+
+        let distance =
+            distance::separation(&us_world, &them_world).map_or(0.0, |manifold| -manifold.depth());
+
+        if distance <= TOLERANCE {
+            let contact = us_world.collide_with_margin(&them_world, TOLERANCE * 2.0)?;
+            return Some(TimeOfImpact { time, contact });
+        }
+
+        if max_displacement <= f32::EPSILON {
+            // Neither collider moves, so they can never get any closer
+            return None;
+        }
+
+        time += distance / max_displacement;
+        if time > 1.0 {
+            return None;
+        }
+    }
+
+    // Advancing this slowly means that the colliders are sliding past each other without getting any closer,
+    // rather than heading towards each other
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(x: f32, y: f32) -> GlobalTransform {
+        Transform::from_xyz(x, y, 0.0).into()
+    }
+
+    #[test]
+    fn catches_tunnelling_colliders() {
+        let ball = Collider::Circle(Circle::new(Vec2::ZERO, 0.5));
+        let wall = Collider::Rectangle(Rectangle::new(0.0, -5.0, 0.1, 5.0));
+        let sweep = Sweep::new(at(-10.0, 0.0), at(10.0, 0.0));
+
+        // The ball is clear of the wall at both ends of the sweep
+        assert!(ball.to_transformed(&sweep.start).collide(&wall).is_none());
+        assert!(ball.to_transformed(&sweep.end).collide(&wall).is_none());
+
+        let toi = ball
+            .time_of_impact(&sweep, &wall, &Sweep::stationary(at(0.0, 0.0)))
+            .unwrap();
+        assert!((toi.time - (9.5 / 20.0)).abs() < 1e-3);
+        assert!(toi.contact.normal().abs_diff_eq(Vec2::X, 1e-3));
+    }
+
+    #[test]
+    fn colliders_moving_towards_each_other() {
+        let ball = Collider::Circle(Circle::new(Vec2::ZERO, 1.0));
+        let us = Sweep::new(at(-5.0, 0.0), at(5.0, 0.0));
+        let them = Sweep::new(at(5.0, 0.0), at(-5.0, 0.0));
+
+        // They meet once they have each moved 4 of their 10 units
+        let toi = ball.time_of_impact(&us, &ball, &them).unwrap();
+        assert!((toi.time - 0.4).abs() < 1e-3);
+    }
+
+    #[test]
+    fn overlapping_colliders_hit_immediately() {
+        let ball = Collider::Circle(Circle::new(Vec2::ZERO, 1.0));
+        let sweep = Sweep::new(at(0.0, 0.0), at(5.0, 0.0));
+
+        let toi = ball
+            .time_of_impact(&sweep, &ball, &Sweep::stationary(at(0.5, 0.0)))
+            .unwrap();
+        assert_eq!(toi.time, 0.0);
+    }
+
+    #[test]
+    fn misses() {
+        let ball = Collider::Circle(Circle::new(Vec2::ZERO, 0.5));
+        let wall = Collider::Rectangle(Rectangle::new(0.0, -5.0, 0.1, 5.0));
+        let wall_sweep = Sweep::stationary(at(0.0, 0.0));
+
+        // Passing above the wall
+        let over = Sweep::new(at(-10.0, 6.0), at(10.0, 6.0));
+        assert!(ball.time_of_impact(&over, &wall, &wall_sweep).is_none());
+
+        // Stopping short of the wall
+        let short = Sweep::new(at(-10.0, 0.0), at(-1.0, 0.0));
+        assert!(ball.time_of_impact(&short, &wall, &wall_sweep).is_none());
+    }
+
+    #[test]
+    fn near_miss_sliding_past_wall() {
+        let ball = Collider::Circle(Circle::new(Vec2::ZERO, 0.5));
+        let wall = Collider::from(
+            ConvexPolygon::new(vec![
+                Vec2::new(0.0, 0.0),
+                Vec2::new(10.0, 10.0),
+                Vec2::new(11.0, 9.0),
+                Vec2::new(1.0, -1.0),
+            ])
+            .unwrap(),
+        );
+        let wall_sweep = Sweep::stationary(at(0.0, 0.0));
+
+        // Moving parallel to the wall, with a gap of 0.05 between them
+        let gap = Vec2::new(-1.0, 1.0).normalize() * 0.55;
+        let start = Vec2::new(1.0, 1.0) + gap;
+        let end = Vec2::new(9.0, 9.0) + gap;
+        let sweep = Sweep::new(at(start.x, start.y), at(end.x, end.y));
+        assert!(ball.time_of_impact(&sweep, &wall, &wall_sweep).is_none());
+
+        let transform = at(start.x, start.y);
+        assert!(ball
+            .cast_shape(&transform, end - start, &wall, &at(0.0, 0.0))
+            .is_none());
+    }
+
+    #[test]
+    fn cast_shape_hits_wall() {
+        let ball = Collider::Circle(Circle::new(Vec2::ZERO, 0.5));
+        let wall = Collider::Rectangle(Rectangle::new(0.0, -5.0, 0.1, 5.0));
+
+        let hit = ball
+            .cast_shape(&at(-10.0, 0.0), Vec2::new(20.0, 0.0), &wall, &at(0.0, 0.0))
+            .unwrap();
+        assert!((hit.toi - (9.5 / 20.0)).abs() < 1e-3);
+        assert!(hit.them.abs_diff_eq(Vec2::ZERO, 1e-2));
+    }
+}
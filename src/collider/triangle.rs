@@ -41,6 +41,41 @@ impl Transformable for Triangle {
     }
 }
 
+impl Shape for Triangle {
+    fn support_point(&self, direction: Vec2) -> Vec2 {
+        [self.a, self.b, self.c]
+            .into_iter()
+            .max_by(|a, b| a.dot(direction).total_cmp(&b.dot(direction)))
+            .unwrap_or_default()
+    }
+
+    fn contains(&self, point: Vec2) -> bool {
+        let ab = (self.b - self.a).perp_dot(point - self.a);
+        let bc = (self.c - self.b).perp_dot(point - self.b);
+        let ca = (self.a - self.c).perp_dot(point - self.c);
+
+        (ab >= 0.0 && bc >= 0.0 && ca >= 0.0) || (ab <= 0.0 && bc <= 0.0 && ca <= 0.0)
+    }
+
+    fn feature_at(&self, point: Vec2) -> FeatureId {
+        polygon::outline_feature(&[self.a, self.b, self.c], point)
+    }
+
+    fn cast_ray(&self, ray: &Ray2d, max_toi: f32, solid: bool) -> Option<RayHit> {
+        let edges = [
+            Line::new(self.a, self.b),
+            Line::new(self.b, self.c),
+            Line::new(self.c, self.a),
+        ];
+        ray::cast_ray_edges(ray, edges, max_toi, solid, self.contains(ray.origin))
+    }
+
+    #[cfg(feature = "debug-draw")]
+    fn debug_geometry(&self, b: &mut Builder) {
+        self.add_geometry(b);
+    }
+}
+
 impl Collides<Capsule> for Triangle {
     fn collide(&self, other: &Capsule) -> Option<ContactManifold> {
         todo!()
@@ -0,0 +1,148 @@
+use super::*;
+use std::f32::consts::{PI, TAU};
+
+/// How many vertices curved outlines are approximated with.
+const CURVED_OUTLINE_SAMPLES: usize = 32;
+
+/// How far either side of each vertex rays are cast, in radians, so that they can slip past corners.
+const CORNER_ANGLE: f32 = 1e-4;
+
+/// The region that is visible from `origin`, within `bounds`, when looking past the outlines of `occluders`.
+///
+/// Circles, capsules and custom shapes are approximated by polygons. Occluders that contain `origin` are ignored,
+/// so that an observer isn't blinded by its own collider. Returns an empty polygon if `origin` lies outside of
+/// `bounds`.
+pub fn visibility_polygon(origin: Vec2, occluders: &[Collider], bounds: Rectangle) -> Polygon {
+    if !bounds.contains(origin) {
+        return Polygon::new(Vec::new());
+    }
+
+    let mut edges: Vec<Line> = occluders
+        .iter()
+        .filter(|occluder| !occluder.contains(origin))
+        .flat_map(occluder_edges)
+        .collect();
+    edges.extend([bounds.bottom(), bounds.right(), bounds.top(), bounds.left()]);
+
+    // Every vertex can change what's visible, so cast a ray at each of them, as well as either side of them
+    let mut angles: Vec<f32> = edges
+        .iter()
+        .flat_map(|edge| [edge.start, edge.end])
+        .filter(|&vertex| bounds.contains(vertex) && vertex != origin)
+        .flat_map(|vertex| {
+            let angle = (vertex - origin).y.atan2((vertex - origin).x);
+            [angle - CORNER_ANGLE, angle, angle + CORNER_ANGLE]
+        })
+        .map(|angle| {
+            // Keep every angle in the same turn, so that sorting them sweeps around exactly once
+            if angle < -PI {
+                angle + TAU
+            } else if angle >= PI {
+                angle - TAU
+            } else {
+                angle
+            }
+        })
+        .collect();
+    angles.sort_by(f32::total_cmp);
+    angles.dedup();
+
+    let mut vertices: Vec<Vec2> = angles
+        .into_iter()
+        .filter_map(|angle| {
+            let ray = Ray2d::new(origin, Vec2::from_angle(angle));
+            edges
+                .iter()
+                .filter_map(|edge| ray::line_toi(&ray, edge))
+                .min_by(f32::total_cmp)
+                .map(|toi| ray.point_at(toi))
+        })
+        .collect();
+
+    vertices.dedup_by(|a, b| a.distance_squared(*b) <= f32::EPSILON);
+    Polygon::new(vertices)
+}
+
+/// The edges of `collider` that block visibility.
+fn occluder_edges(collider: &Collider) -> Vec<Line> {
+    match collider {
+        Collider::Point(_) => Vec::new(),
+        _ => polygon::EdgesIterator::new(&outline(collider)).collect(),
+    }
+}
+
+/// The vertices around the outline of `collider`, with curved shapes approximated by polygons.
+///
+/// Points are outlined by their single vertex.
+pub(crate) fn outline(collider: &Collider) -> Vec<Vec2> {
+    match collider {
+        Collider::ConvexPolygon(polygon) => polygon.vertices().to_vec(),
+        Collider::Point(point) => vec![point.0],
+        Collider::Polygon(polygon) => polygon.vertices.clone(),
+        Collider::Rectangle(rect) => {
+            let (min, max) = (rect.min(), rect.max());
+            vec![min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)]
+        }
+        Collider::Capsule(_) | Collider::Circle(_) | Collider::Custom(_) => {
+            let samples = 0..CURVED_OUTLINE_SAMPLES;
+            samples
+                .map(|i| {
+                    let angle = (i as f32 / CURVED_OUTLINE_SAMPLES as f32) * TAU;
+                    collider.support_point(Vec2::from_angle(angle))
+                })
+                .collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn area(polygon: &Polygon) -> f32 {
+        polygon
+            .edges()
+            .map(|edge| edge.start.perp_dot(edge.end))
+            .sum::<f32>()
+            .abs()
+            / 2.0
+    }
+
+    #[test]
+    fn sees_all_of_the_bounds_without_occluders() {
+        let bounds = Rectangle::new(0.0, 0.0, 10.0, 10.0);
+        let visible = visibility_polygon(Vec2::new(2.0, 3.0), &[], bounds);
+
+        assert!((area(&visible) - 100.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn occluders_cast_shadows() {
+        let bounds = Rectangle::new(0.0, 0.0, 10.0, 10.0);
+        let wall = Collider::from(Rectangle::new(4.0, 4.0, 6.0, 6.0));
+        let visible = visibility_polygon(Vec2::new(1.0, 5.0), &[wall], bounds);
+
+        assert!(visible.contains(Vec2::new(3.0, 5.0)));
+        assert!(!visible.contains(Vec2::new(5.0, 5.0)));
+        assert!(!visible.contains(Vec2::new(9.0, 5.0)));
+        assert!(visible.contains(Vec2::new(9.0, 1.0)));
+        assert!(visible.contains(Vec2::new(9.0, 9.0)));
+    }
+
+    #[test]
+    fn ignores_occluders_containing_the_origin() {
+        let bounds = Rectangle::new(0.0, 0.0, 10.0, 10.0);
+        let own = Collider::from(Circle::new(Vec2::new(5.0, 5.0), 1.0));
+        let visible = visibility_polygon(Vec2::new(5.0, 5.0), &[own], bounds);
+
+        assert!((area(&visible) - 100.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn sees_nothing_from_outside_of_the_bounds() {
+        let bounds = Rectangle::new(0.0, 0.0, 10.0, 10.0);
+        let visible = visibility_polygon(Vec2::new(-1.0, 5.0), &[], bounds);
+
+        assert!(visible.vertices.is_empty());
+    }
+}
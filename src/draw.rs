@@ -1,6 +1,16 @@
-use super::collider::{Collider, Colliding};
+use super::{
+    collider::{Collider, ColliderOffset, Colliding},
+    query::{RayCaster, RayHits},
+    transform_ext::TransformPoint2,
+};
 use bevy::prelude::*;
-use bevy_prototype_lyon::prelude::{DrawMode, GeometryBuilder, ShapePlugin, StrokeMode};
+use bevy_prototype_lyon::prelude::{
+    tess::path::{path::Builder, Winding},
+    DrawMode, Geometry, GeometryBuilder, ShapePlugin, StrokeMode,
+};
+
+/// The radius of the circles drawn at each point that a [`RayCaster`] hits.
+const RAY_HIT_RADIUS: f32 = 3.0;
 
 pub struct DrawPlugin;
 
@@ -11,7 +21,11 @@ impl Plugin for DrawPlugin {
             .add_system(draw_colliders)
             .add_system(update_colliders)
             .add_system(update_colors)
-            .add_system(undraw_colliders);
+            .add_system(update_offsets)
+            .add_system(undraw_colliders)
+            .add_system(draw_ray_casters)
+            .add_system(update_ray_caster_shapes)
+            .add_system(undraw_ray_casters);
     }
 }
 
@@ -42,12 +56,75 @@ pub struct DrawCollider;
 #[derive(Clone, Copy, Component, Debug, PartialEq, Eq)]
 pub struct DrawColliderShape(Entity);
 
+#[derive(Bundle, Default, Debug)]
+pub struct RayCasterDrawBundle {
+    pub draw: DrawRayCaster,
+    pub visibility: VisibilityBundle,
+}
+
+/// Draws the ray of a [`RayCaster`], along with a circle at each point that it hits.
+#[derive(Clone, Copy, Component, Default, Debug)]
+pub struct DrawRayCaster;
+
+#[derive(Clone, Copy, Component, Debug, PartialEq, Eq)]
+pub struct DrawRayCasterShape(Entity);
+
+/// The ray of a [`RayCaster`] and the points that it hit, in the local space of its entity.
+struct RayCasterGeometry {
+    start: Vec2,
+    end: Vec2,
+    hits: Vec<Vec2>,
+}
+
+impl RayCasterGeometry {
+    fn new(transform: &GlobalTransform, caster: &RayCaster, hits: Option<&RayHits>) -> Self {
+        let hits = hits.map_or(&[][..], |hits| hits.0.as_slice());
+        let ray = caster.to_world(transform);
+        let to_local = transform.affine().inverse();
+
+        // Rays that reach infinitely far are only drawn up to their furthest hit
+        let length = if caster.max_distance.is_finite() {
+            caster.max_distance
+        } else {
+            hits.last().map_or(0.0, |(_, hit)| hit.toi)
+        };
+
+        Self {
+            start: caster.origin,
+            end: to_local.transform_point2(ray.point_at(length)),
+            hits: hits
+                .iter()
+                .map(|(_, hit)| to_local.transform_point2(hit.point))
+                .collect(),
+        }
+    }
+}
+
+impl Geometry for RayCasterGeometry {
+    fn add_geometry(&self, b: &mut Builder) {
+        b.begin((self.start.x, self.start.y).into());
+        b.line_to((self.end.x, self.end.y).into());
+        b.end(false);
+
+        for hit in &self.hits {
+            b.add_circle((hit.x, hit.y).into(), RAY_HIT_RADIUS, Winding::Positive);
+        }
+    }
+}
+
+fn ray_caster_color(hits: Option<&RayHits>, colors: &DrawColors) -> DrawMode {
+    match hits {
+        Some(hits) if !hits.0.is_empty() => colors.colliding,
+        _ => colors.disjoint,
+    }
+}
+
 pub fn draw_colliders(
     mut commands: Commands,
-    query: Query<(Entity, &Collider, &Colliding), Added<DrawCollider>>,
+    query: Query<(Entity, &Collider, Option<&ColliderOffset>, &Colliding), Added<DrawCollider>>,
     colors: Res<DrawColors>,
 ) {
-    for (entity, collider, colliding) in query.iter() {
+    for (entity, collider, offset, colliding) in query.iter() {
         let color = if colliding.0.is_empty() {
             colors.disjoint
         } else {
@@ -57,7 +134,7 @@ pub fn draw_colliders(
         let mut e = commands.spawn(GeometryBuilder::build_as(
             collider,
             color,
-            Transform::default(),
+            offset.map(ColliderOffset::to_transform).unwrap_or_default(),
         ));
         e.set_parent(entity);
 
@@ -68,10 +145,18 @@ pub fn draw_colliders(
 
 pub fn update_colliders(
     mut commands: Commands,
-    query: Query<(&Collider, &Colliding, &DrawColliderShape), Changed<Collider>>,
+    query: Query<
+        (
+            &Collider,
+            Option<&ColliderOffset>,
+            &Colliding,
+            &DrawColliderShape,
+        ),
+        Changed<Collider>,
+    >,
     colors: Res<DrawColors>,
 ) {
-    for (collider, colliding, DrawColliderShape(entity)) in query.iter() {
+    for (collider, offset, colliding, DrawColliderShape(entity)) in query.iter() {
         let color = if colliding.0.is_empty() {
             colors.disjoint
         } else {
@@ -81,7 +166,7 @@ pub fn update_colliders(
         commands.entity(*entity).insert(GeometryBuilder::build_as(
             collider,
             color,
-            Transform::default(),
+            offset.map(ColliderOffset::to_transform).unwrap_or_default(),
         ));
     }
 }
@@ -103,6 +188,27 @@ pub fn update_colors(
     }
 }
 
+pub fn update_offsets(
+    collider_query: Query<(&ColliderOffset, &DrawColliderShape), Changed<ColliderOffset>>,
+    removed_offset: RemovedComponents<ColliderOffset>,
+    draw_query: Query<&DrawColliderShape>,
+    mut shape_query: Query<&mut Transform>,
+) {
+    for (offset, DrawColliderShape(entity)) in collider_query.iter() {
+        if let Ok(mut transform) = shape_query.get_mut(*entity) {
+            *transform = offset.to_transform();
+        }
+    }
+
+    for entity in removed_offset.iter() {
+        if let Ok(DrawColliderShape(e)) = draw_query.get(entity) {
+            if let Ok(mut transform) = shape_query.get_mut(*e) {
+                *transform = Transform::default();
+            }
+        }
+    }
+}
+
 pub fn undraw_colliders(
     mut commands: Commands,
     removed_collider: RemovedComponents<Collider>,
@@ -123,3 +229,64 @@ pub fn undraw_colliders(
         }
     }
 }
+
+pub fn draw_ray_casters(
+    mut commands: Commands,
+    query: Query<(Entity, &GlobalTransform, &RayCaster, Option<&RayHits>), Added<DrawRayCaster>>,
+    colors: Res<DrawColors>,
+) {
+    for (entity, transform, caster, hits) in query.iter() {
+        let mut e = commands.spawn(GeometryBuilder::build_as(
+            &RayCasterGeometry::new(transform, caster, hits),
+            ray_caster_color(hits, &colors),
+            Transform::default(),
+        ));
+        e.set_parent(entity);
+
+        let id = e.id();
+        commands.entity(entity).insert(DrawRayCasterShape(id));
+    }
+}
+
+/// The components of a ray caster that its drawn shape is built from.
+type RayCasterShapeQuery<'a> = (
+    &'a GlobalTransform,
+    &'a RayCaster,
+    Option<&'a RayHits>,
+    &'a DrawRayCasterShape,
+);
+
+/// Ray casters whose drawn shape is out of date.
+type RayCasterShapeChanged = Or<(
+    Changed<GlobalTransform>,
+    Changed<RayCaster>,
+    Changed<RayHits>,
+)>;
+
+pub fn update_ray_caster_shapes(
+    mut commands: Commands,
+    query: Query<RayCasterShapeQuery, RayCasterShapeChanged>,
+    colors: Res<DrawColors>,
+) {
+    for (transform, caster, hits, DrawRayCasterShape(entity)) in query.iter() {
+        commands.entity(*entity).insert(GeometryBuilder::build_as(
+            &RayCasterGeometry::new(transform, caster, hits),
+            ray_caster_color(hits, &colors),
+            Transform::default(),
+        ));
+    }
+}
+
+pub fn undraw_ray_casters(
+    mut commands: Commands,
+    removed_caster: RemovedComponents<RayCaster>,
+    removed_draw: RemovedComponents<DrawRayCaster>,
+    query: Query<Option<&DrawRayCasterShape>>,
+) {
+    for entity in removed_caster.iter().chain(removed_draw.iter()) {
+        if let Ok(Some(DrawRayCasterShape(e))) = query.get(entity) {
+            commands.entity(*e).despawn();
+            commands.entity(entity).remove::<DrawRayCasterShape>();
+        }
+    }
+}
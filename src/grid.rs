@@ -0,0 +1,355 @@
+use super::{
+    collider::*,
+    layers::{CollisionLayerFlags, CollisionLayers},
+    plugin::ColliderRegions,
+    query::ColliderCache,
+};
+use bevy::{prelude::*, utils::HashMap};
+
+/// The most cells that the bounds are split into along each axis.
+const MAX_CELLS_PER_SIDE: f32 = 2048.0;
+
+/// Configures how the [`OccupancyGrid`] resource is rasterised.
+///
+/// Insert it as a resource to start rasterising every collider on matching layers into the grid. The grid is
+/// rasterised from scratch whenever this changes.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct OccupancyGridSettings {
+    /// The region that the grid covers.
+    pub bounds: Rectangle,
+    /// The width and height of each cell. Cells are never smaller than 1/2048 of the longest side of `bounds`.
+    pub cell_size: f32,
+    /// Only colliders whose layers intersect these layers occupy cells. By default, every collider does.
+    pub layers: CollisionLayers,
+}
+
+impl OccupancyGridSettings {
+    pub fn new(bounds: Rectangle, cell_size: f32) -> Self {
+        Self {
+            bounds,
+            cell_size,
+            layers: CollisionLayers::Inclusive(CollisionLayerFlags::EVERYTHING),
+        }
+    }
+
+    pub fn with_layers(mut self, layers: CollisionLayers) -> Self {
+        self.layers = layers;
+        self
+    }
+
+    /// The cell size, grown if needed so that `bounds` isn't split into more cells than the limit.
+    fn limited_cell_size(&self) -> f32 {
+        self.cell_size
+            .max(self.bounds.size().max_element() / MAX_CELLS_PER_SIDE)
+    }
+
+    /// The number of columns and rows of cells that `bounds` is split into.
+    fn grid_size(&self) -> UVec2 {
+        (self.bounds.size() / self.limited_cell_size())
+            .ceil()
+            .max(Vec2::ONE)
+            .as_uvec2()
+    }
+}
+
+/// How costly it is to pass through the cells of an [`OccupancyGrid`] that a collider overlaps, from 1 for
+/// barely any cost to [`OccupancyCost::BLOCKED`] for impassable.
+///
+/// Colliders without one block the cells they overlap.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OccupancyCost(pub u8);
+
+impl OccupancyCost {
+    pub const BLOCKED: Self = Self(u8::MAX);
+}
+
+impl Default for OccupancyCost {
+    fn default() -> Self {
+        Self::BLOCKED
+    }
+}
+
+/// A grid recording which cells are overlapped by colliders, see [`OccupancyGridSettings`].
+///
+/// Each cell holds the highest [`OccupancyCost`] of the colliders overlapping it, or 0 if it's free.
+#[derive(Resource, Clone, Default, Debug)]
+pub struct OccupancyGrid {
+    settings: Option<OccupancyGridSettings>,
+    size: UVec2,
+    /// The cost of each cell, row by row from the bottom left.
+    costs: Vec<u8>,
+}
+
+impl OccupancyGrid {
+    /// Rasterises `colliders`, which should be in world space, along with their costs.
+    pub fn rasterise(
+        settings: OccupancyGridSettings,
+        colliders: &[(Collider, OccupancyCost)],
+    ) -> Self {
+        let size = settings.grid_size();
+        let cells = (size.x as usize)
+            .checked_mul(size.y as usize)
+            .expect("the number of cells is limited relative to the bounds");
+        let bounds = settings.bounds;
+        let mut grid = Self {
+            settings: Some(settings),
+            size,
+            costs: vec![0; cells],
+        };
+        grid.update(colliders, [bounds]);
+        grid
+    }
+
+    /// Rasterises the cells overlapping each of `regions` again, such as the bounding boxes of colliders before
+    /// and after they moved. `colliders` should include every collider, not just those that changed.
+    pub fn update(
+        &mut self,
+        colliders: &[(Collider, OccupancyCost)],
+        regions: impl IntoIterator<Item = Rectangle>,
+    ) {
+        let mut dirty = vec![false; self.costs.len()];
+        let mut regions_aabb: Option<Rectangle> = None;
+        for region in regions {
+            for cell in self.cells_overlapping(region) {
+                let index = self.index(cell);
+                dirty[index] = true;
+                self.costs[index] = 0;
+            }
+            regions_aabb = Some(regions_aabb.map_or(region, |aabb| aabb.union(region)));
+        }
+
+        let Some(regions_aabb) = regions_aabb else { return; };
+
+        for (collider, cost) in colliders {
+            let aabb = collider.aabb();
+            if !aabb.intersects(&regions_aabb) {
+                continue;
+            }
+
+            for cell in self.cells_overlapping(aabb) {
+                let index = self.index(cell);
+                if dirty[index] && cost.0 > self.costs[index] && self.overlaps(collider, cell) {
+                    self.costs[index] = cost.0;
+                }
+            }
+        }
+    }
+
+    pub fn settings(&self) -> Option<&OccupancyGridSettings> {
+        self.settings.as_ref()
+    }
+
+    /// The number of columns and rows of cells.
+    pub fn size(&self) -> UVec2 {
+        self.size
+    }
+
+    /// The cost of every cell, row by row from the bottom left.
+    pub fn costs(&self) -> &[u8] {
+        &self.costs
+    }
+
+    /// The cost of the given cell, or `None` if it's outside of the grid.
+    pub fn cost(&self, cell: UVec2) -> Option<u8> {
+        cell.cmplt(self.size)
+            .all()
+            .then(|| self.costs[self.index(cell)])
+    }
+
+    /// Whether the given cell is overlapped by a collider with [`OccupancyCost::BLOCKED`]. Cells outside of the
+    /// grid are blocked.
+    pub fn is_blocked(&self, cell: UVec2) -> bool {
+        self.cost(cell)
+            .is_none_or(|cost| cost == OccupancyCost::BLOCKED.0)
+    }
+
+    /// The cell containing `point`, or `None` if it's outside of the grid.
+    pub fn cell_at(&self, point: Vec2) -> Option<UVec2> {
+        let settings = self.settings.as_ref()?;
+        let cell = ((point - settings.bounds.min()) / settings.limited_cell_size()).floor();
+        (cell.cmpge(Vec2::ZERO).all() && cell.cmplt(self.size.as_vec2()).all())
+            .then(|| cell.as_uvec2())
+    }
+
+    /// The area covered by the given cell.
+    pub fn cell_rect(&self, cell: UVec2) -> Rectangle {
+        let Some(settings) = &self.settings else { return Rectangle::default(); };
+        let cell_size = settings.limited_cell_size();
+        let min = settings.bounds.min() + (cell.as_vec2() * cell_size);
+        Rectangle::from_corners(min, min + cell_size)
+    }
+
+    fn index(&self, cell: UVec2) -> usize {
+        cell.x as usize + (cell.y as usize * self.size.x as usize)
+    }
+
+    /// The cells that overlap `rect`.
+    fn cells_overlapping(&self, rect: Rectangle) -> impl Iterator<Item = UVec2> {
+        let (min, max) = match &self.settings {
+            Some(settings) if rect.intersects(&settings.bounds) => {
+                let to_cell = |point: Vec2| {
+                    ((point - settings.bounds.min()) / settings.limited_cell_size())
+                        .floor()
+                        .clamp(Vec2::ZERO, (self.size - 1).as_vec2())
+                        .as_uvec2()
+                };
+                (to_cell(rect.min()), to_cell(rect.max()) + 1)
+            }
+            _ => (UVec2::ZERO, UVec2::ZERO),
+        };
+
+        (min.y..max.y).flat_map(move |y| (min.x..max.x).map(move |x| UVec2::new(x, y)))
+    }
+
+    /// Whether `collider` overlaps the inside of `cell`, not counting when it only touches its edges.
+    fn overlaps(&self, collider: &Collider, cell: UVec2) -> bool {
+        let rect = self.cell_rect(cell);
+        let rect = rect.inset(-rect.width() * 1e-3);
+        collider.collide(&Collider::from(rect)).is_some()
+    }
+}
+
+type OccupantQuery<'a> = (
+    Entity,
+    &'a GlobalTransform,
+    &'a Collider,
+    Option<&'a ColliderOffset>,
+    &'a CollisionLayers,
+    Option<&'a OccupancyCost>,
+);
+
+type OccupantChanged = Or<(
+    Changed<Collider>,
+    Changed<ColliderOffset>,
+    Changed<CollisionLayers>,
+    Changed<GlobalTransform>,
+    Changed<OccupancyCost>,
+)>;
+
+pub(crate) fn update_occupancy_grid(
+    settings: Option<Res<OccupancyGridSettings>>,
+    mut grid: ResMut<OccupancyGrid>,
+    cache: Res<ColliderCache>,
+    colliders: Query<OccupantQuery>,
+    changed: Query<Entity, OccupantChanged>,
+    removed: RemovedComponents<Collider>,
+    mut regions: Local<ColliderRegions>,
+) {
+    let Some(settings) = settings else { return; };
+
+    // Colliders that were despawned only show up as removals
+    let unchanged = changed.is_empty() && removed.iter().next().is_none();
+    if unchanged && grid.settings() == Some(&settings) {
+        return;
+    }
+
+    let current: HashMap<Entity, (Collider, OccupancyCost)> = colliders
+        .iter()
+        .filter(|(_, _, _, _, layers, _)| settings.layers.intersects(layers))
+        .map(|(entity, transform, collider, offset, _, cost)| {
+            let world = cache.world(entity).cloned();
+            let world = world.unwrap_or_else(|| collider.to_world(transform, offset));
+            (entity, (world, cost.copied().unwrap_or_default()))
+        })
+        .collect();
+    let aabbs = current
+        .iter()
+        .map(|(&entity, (collider, _))| (entity, collider.aabb()))
+        .collect();
+    let changed_regions = regions.update(aabbs, changed.iter());
+    let colliders: Vec<(Collider, OccupancyCost)> = current.into_values().collect();
+
+    if grid.settings() != Some(&settings) {
+        *grid = OccupancyGrid::rasterise(settings.clone(), &colliders);
+    } else if !changed_regions.is_empty() {
+        grid.update(&colliders, changed_regions);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::{add_collision_stage, ColliderBundle};
+
+    #[test]
+    fn rasterises_the_highest_cost() {
+        let settings = OccupancyGridSettings::new(Rectangle::new(0.0, 0.0, 10.0, 10.0), 1.0);
+        let colliders = [
+            (
+                Collider::from(Circle::new(Vec2::new(5.0, 5.0), 1.5)),
+                OccupancyCost::BLOCKED,
+            ),
+            (
+                Collider::from(Rectangle::new(0.0, 0.0, 2.0, 1.0)),
+                OccupancyCost(3),
+            ),
+            (
+                Collider::from(Rectangle::new(1.0, 0.0, 2.0, 1.0)),
+                OccupancyCost(7),
+            ),
+        ];
+        let grid = OccupancyGrid::rasterise(settings, &colliders);
+
+        assert_eq!(grid.size(), UVec2::new(10, 10));
+        assert!(grid.is_blocked(UVec2::new(5, 5)));
+        assert_eq!(grid.cost(UVec2::new(0, 0)), Some(3));
+        assert_eq!(grid.cost(UVec2::new(1, 0)), Some(7));
+        // Only touches the edge of the rectangles
+        assert_eq!(grid.cost(UVec2::new(2, 0)), Some(0));
+        assert!(grid.is_blocked(UVec2::new(10, 0)));
+    }
+
+    #[test]
+    fn updates_only_dirty_regions() {
+        let settings = OccupancyGridSettings::new(Rectangle::new(0.0, 0.0, 10.0, 10.0), 1.0);
+        let before = Collider::from(Circle::new(Vec2::new(5.0, 5.0), 1.5));
+        let after = Collider::from(Circle::new(Vec2::new(8.5, 8.5), 0.5));
+        let mut grid =
+            OccupancyGrid::rasterise(settings, &[(before.clone(), OccupancyCost::BLOCKED)]);
+
+        grid.update(
+            &[(after.clone(), OccupancyCost::BLOCKED)],
+            [before.aabb(), after.aabb()],
+        );
+        assert!(!grid.is_blocked(UVec2::new(5, 5)));
+        assert!(grid.is_blocked(UVec2::new(8, 8)));
+
+        // Colliders outside of the regions aren't rasterised again, even if they're missing
+        let other = Collider::from(Circle::new(Vec2::new(1.5, 1.5), 0.5));
+        grid.update(&[(other.clone(), OccupancyCost::BLOCKED)], [other.aabb()]);
+        assert!(grid.is_blocked(UVec2::new(1, 1)));
+        assert!(grid.is_blocked(UVec2::new(8, 8)));
+    }
+
+    #[test]
+    fn limits_cells_relative_to_the_bounds() {
+        let bounds = Rectangle::new(0.0, 0.0, 4096.0, 1024.0);
+        let settings = OccupancyGridSettings::new(bounds, 0.0);
+
+        assert_eq!(settings.grid_size(), UVec2::new(2048, 512));
+    }
+
+    #[test]
+    fn clears_despawned_colliders() {
+        let mut app = App::new();
+        add_collision_stage(&mut app);
+        let bounds = Rectangle::new(0.0, 0.0, 10.0, 10.0);
+        app.insert_resource(OccupancyGridSettings::new(bounds, 1.0));
+        let wall = app
+            .world
+            .spawn(ColliderBundle::rect(Rect::new(2.0, 2.0, 4.0, 4.0)))
+            .id();
+        app.update();
+        assert!(app
+            .world
+            .resource::<OccupancyGrid>()
+            .is_blocked(UVec2::new(3, 3)));
+
+        app.world.despawn(wall);
+        app.update();
+        assert!(!app
+            .world
+            .resource::<OccupancyGrid>()
+            .is_blocked(UVec2::new(3, 3)));
+    }
+}
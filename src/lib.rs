@@ -1,24 +1,37 @@
 pub mod collider;
+pub mod grid;
 pub mod layers;
+pub mod navmesh;
 pub mod plugin;
+pub mod query;
+pub mod sdf;
 pub mod transform_ext;
+pub mod view;
 
 #[cfg(feature = "debug-draw")]
 pub mod draw;
 
 pub mod prelude {
     pub use super::collider::{
-        Capsule, Circle, Collider, Collides, Colliding, ContactManifold, Line, Point, Polygon,
-        Rectangle, Transformable, Triangle,
+        Capsule, Ccd, Circle, Collider, ColliderOffset, Collides, Colliding, ContactManifold,
+        ContactSkin, ConvexPolygon, JoinStyle, Line, Point, Polygon, Ray2d, RayHit, Rectangle,
+        Transformable, Triangle,
     };
     pub use super::draw::{
-        ColliderDrawBundle, DrawCollider, DrawColliderShape, DrawColors, DrawPlugin,
+        ColliderDrawBundle, DrawCollider, DrawColliderShape, DrawColors, DrawPlugin, DrawRayCaster,
+        DrawRayCasterShape, RayCasterDrawBundle,
     };
+    pub use super::grid::{OccupancyCost, OccupancyGrid, OccupancyGridSettings};
     pub use super::layers::{CollisionLayerFlags, CollisionLayers, CollisionLayersLabel};
+    pub use super::navmesh::{NavMesh, NavMeshObstacle, NavMeshSettings};
     pub use super::plugin::{
         ColliderBundle, CollisionBegan, CollisionEnded, CollisionEvent, CollisionPlugin,
-        CollisionStage, FindCollidingPairs,
+        CollisionStage, FindCollidingPairs, UpdateCollidersInView, UpdateNavMesh,
+        UpdateOccupancyGrid, UpdateRayCasters,
     };
+    pub use super::query::{QueryFilter, RayCaster, RayHits, SpatialQuery};
+    pub use super::sdf::SignedDistanceField;
+    pub use super::view::{CollidersInView, ViewEntered, ViewEvent, ViewLeft};
     pub use bevy_overlap_2d_derive::CollisionLayersLabel;
 }
 
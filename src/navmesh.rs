@@ -0,0 +1,889 @@
+use super::{
+    collider::*,
+    layers::{CollisionLayerFlags, CollisionLayers},
+    plugin::ColliderRegions,
+    query::ColliderCache,
+};
+use bevy::{
+    math::DVec2,
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use std::{cmp::Ordering, collections::BinaryHeap, ops::Range};
+
+/// The arc tolerance used to round off inflated obstacles, as a fraction of the agent radius.
+const ARC_TOLERANCE: f32 = 0.1;
+
+/// The most tiles that the bounds are split into along each axis.
+const MAX_TILES_PER_SIDE: f32 = 32.0;
+
+/// The most edges that each tile border is split into, which bounds the number of points in each tile.
+const MAX_EDGES_PER_TILE_SIDE: f32 = 16.0;
+
+/// Configures how the [`NavMesh`] resource is generated.
+///
+/// Insert it as a resource to start generating a navmesh around every collider with a [`NavMeshObstacle`]. The
+/// navmesh is regenerated from scratch whenever this changes.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct NavMeshSettings {
+    /// The region that agents can walk around in.
+    pub bounds: Rectangle,
+    /// How far agents keep away from obstacles.
+    pub agent_radius: f32,
+    /// Only obstacles whose layers intersect these layers are walked around. By default, every obstacle is.
+    pub layers: CollisionLayers,
+    /// The size of the square tiles that `bounds` is split into. When obstacles change, only the tiles around
+    /// them are regenerated. Tiles are never smaller than 1/32 of the longest side of `bounds`.
+    pub tile_size: f32,
+    /// The longest that edges along tile borders and obstacle outlines can be. Shorter edges follow obstacles
+    /// more closely, at the cost of more triangles. Edges are never shorter than 1/16 of the tile size.
+    pub max_edge_length: f32,
+}
+
+impl NavMeshSettings {
+    pub fn new(bounds: Rectangle, agent_radius: f32) -> Self {
+        let tile_size = bounds.size().max_element() / 8.0;
+        Self {
+            bounds,
+            agent_radius,
+            layers: CollisionLayers::Inclusive(CollisionLayerFlags::EVERYTHING),
+            tile_size,
+            max_edge_length: tile_size / 8.0,
+        }
+    }
+
+    pub fn with_layers(mut self, layers: CollisionLayers) -> Self {
+        self.layers = layers;
+        self
+    }
+
+    pub fn with_tile_size(mut self, tile_size: f32) -> Self {
+        self.tile_size = tile_size;
+        self
+    }
+
+    pub fn with_max_edge_length(mut self, max_edge_length: f32) -> Self {
+        self.max_edge_length = max_edge_length;
+        self
+    }
+
+    /// The tile size, grown if needed so that `bounds` isn't split into more tiles than the limit.
+    fn limited_tile_size(&self) -> f32 {
+        self.tile_size
+            .max(self.bounds.size().max_element() / MAX_TILES_PER_SIDE)
+    }
+
+    /// The max edge length, grown if needed so that tile borders aren't split into more edges than the limit.
+    fn limited_max_edge_length(&self) -> f32 {
+        self.max_edge_length
+            .max(self.limited_tile_size() / MAX_EDGES_PER_TILE_SIDE)
+    }
+
+    /// The number of columns and rows of tiles that `bounds` is split into.
+    fn grid_size(&self) -> (usize, usize) {
+        let tiles = (self.bounds.size() / self.limited_tile_size())
+            .ceil()
+            .max(Vec2::ONE);
+        (tiles.x as usize, tiles.y as usize)
+    }
+
+    fn tile_rect(&self, column: usize, row: usize) -> Rectangle {
+        let min = self.bounds.min();
+        let max = self.bounds.max();
+        let tile_size = self.limited_tile_size();
+        let x = |column: usize| (min.x + (column as f32 * tile_size)).min(max.x);
+        let y = |row: usize| (min.y + (row as f32 * tile_size)).min(max.y);
+        Rectangle::new(x(column), y(row), x(column + 1), y(row + 1))
+    }
+
+    /// The indices of the tiles that overlap `rect`, including those that only share an edge with it.
+    fn tiles_overlapping(&self, rect: Rectangle) -> impl Iterator<Item = usize> {
+        let (columns, rows) = self.grid_size();
+        let (min, max) = if rect.intersects(&self.bounds) {
+            let last = Vec2::new(columns as f32 - 1.0, rows as f32 - 1.0);
+            let to_tile = |point: Vec2| (point - self.bounds.min()) / self.limited_tile_size();
+            (
+                (to_tile(rect.min()).ceil() - 1.0).clamp(Vec2::ZERO, last),
+                to_tile(rect.max()).floor().clamp(Vec2::ZERO, last) + 1.0,
+            )
+        } else {
+            (Vec2::ZERO, Vec2::ZERO)
+        };
+
+        let (min, max) = (min.as_uvec2(), max.as_uvec2());
+        (min.y..max.y).flat_map(move |row| {
+            (min.x..max.x).map(move |column| column as usize + (row as usize * columns))
+        })
+    }
+}
+
+/// Marks a static collider that the [`NavMesh`] is generated around.
+#[derive(Component, Clone, Copy, Default, Debug)]
+pub struct NavMeshObstacle;
+
+/// A triangulated navigation mesh covering the walkable parts of a region, see [`NavMeshSettings`].
+#[derive(Resource, Clone, Default, Debug)]
+pub struct NavMesh {
+    settings: Option<NavMeshSettings>,
+    /// The triangles of each tile, row by row.
+    tiles: Vec<Vec<[Vec2; 3]>>,
+    vertices: Vec<Vec2>,
+    /// Anticlockwise vertex indices, grouped by tile.
+    triangles: Vec<[usize; 3]>,
+    /// The range of `triangles` that came from each tile, so that the tiles double as a spatial index.
+    tile_triangles: Vec<Range<usize>>,
+    /// The triangle on the other side of each edge, where edge `i` runs from vertex `i` to vertex `i + 1`.
+    neighbours: Vec<[Option<usize>; 3]>,
+    /// The edges of each tile that only have a triangle on one side, around obstacles and the edge of the
+    /// navmesh.
+    boundary: Vec<Vec<Line>>,
+}
+
+impl NavMesh {
+    /// Generates a navmesh that keeps agents away from `obstacles`, which should be in world space.
+    pub fn generate(settings: NavMeshSettings, obstacles: &[Collider]) -> Self {
+        let (columns, rows) = settings.grid_size();
+        let bounds = settings.bounds;
+        let mut navmesh = Self {
+            settings: Some(settings),
+            tiles: vec![Vec::new(); columns * rows],
+            ..Default::default()
+        };
+        navmesh.regenerate(obstacles, [bounds]);
+        navmesh
+    }
+
+    /// Regenerates the tiles around each of `regions`, such as the bounding boxes of obstacles before and after
+    /// they moved. `obstacles` should include every obstacle, not just those that changed.
+    pub fn regenerate(
+        &mut self,
+        obstacles: &[Collider],
+        regions: impl IntoIterator<Item = Rectangle>,
+    ) {
+        let Some(settings) = self.settings.clone() else { return; };
+        let (columns, rows) = settings.grid_size();
+
+        // Inflated obstacles reach `agent_radius` beyond their bounding boxes
+        let regions: Vec<Rectangle> = regions
+            .into_iter()
+            .map(|region| region.inset(settings.agent_radius))
+            .collect();
+        let dirty: Vec<(usize, Rectangle)> = (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (column, row)))
+            .map(|(column, row)| (column + (row * columns), settings.tile_rect(column, row)))
+            .filter(|(_, tile)| regions.iter().any(|region| region.intersects(tile)))
+            // Only tiles with some area have anywhere to walk
+            .filter(|(_, tile)| !tile.is_empty() && tile.size().is_finite())
+            .collect();
+
+        if dirty.is_empty() {
+            return;
+        }
+
+        let inflated: Vec<(Rectangle, Polygon)> = obstacles
+            .iter()
+            .filter(|obstacle| {
+                let aabb = obstacle.aabb().inset(settings.agent_radius);
+                dirty.iter().any(|(_, tile)| aabb.intersects(tile))
+            })
+            .flat_map(|obstacle| inflate(obstacle, &settings))
+            .map(|polygon| (polygon_aabb(&polygon), polygon))
+            .collect();
+
+        for (index, tile) in dirty {
+            self.tiles[index] =
+                triangulate_tile(tile, &inflated, settings.limited_max_edge_length());
+        }
+
+        self.rebuild();
+    }
+
+    pub fn settings(&self) -> Option<&NavMeshSettings> {
+        self.settings.as_ref()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.triangles.is_empty()
+    }
+
+    /// The walkable triangles of the navmesh, each wound anticlockwise.
+    pub fn triangles(&self) -> impl Iterator<Item = Triangle> + '_ {
+        self.triangles
+            .iter()
+            .map(|&[a, b, c]| Triangle::new(self.vertices[a], self.vertices[b], self.vertices[c]))
+    }
+
+    /// The shortest path from `start` to `goal` through the navmesh, including both of them, or `None` if there
+    /// is no way between them.
+    ///
+    /// Points that lie off the navmesh, for example because they're too close to an obstacle, are moved to the
+    /// closest point on it first.
+    pub fn find_path(&self, start: Vec2, goal: Vec2) -> Option<Vec<Vec2>> {
+        let (start_triangle, start) = self.locate(start)?;
+        let (goal_triangle, goal) = self.locate(goal)?;
+        let corridor = self.find_corridor(start_triangle, goal_triangle, goal)?;
+
+        let portals: Vec<(Vec2, Vec2)> = corridor
+            .windows(2)
+            .map(|pair| self.portal(pair[0], pair[1]))
+            .collect();
+        let path = funnel(start, goal, &portals);
+
+        // Searching across triangles doesn't always find the straightest corridor, so cut any unnecessary corners
+        Some(self.shortcut(&path))
+    }
+
+    /// Removes the corners of `path` that can be walked straight past.
+    fn shortcut(&self, path: &[Vec2]) -> Vec<Vec2> {
+        let mut shortcut = vec![path[0]];
+        let mut i = 0;
+        while i < path.len() - 1 {
+            i = (i + 2..path.len())
+                .rev()
+                .find(|&j| self.is_walkable(&Line::new(path[i], path[j])))
+                .unwrap_or(i + 1);
+            shortcut.push(path[i]);
+        }
+        shortcut
+    }
+
+    /// Whether `line` stays on the navmesh, assuming that its ends are on it.
+    fn is_walkable(&self, line: &Line) -> bool {
+        let Some(settings) = &self.settings else { return false; };
+
+        // Lines that don't cross the boundary are either entirely on the navmesh or entirely off it, such as when
+        // cutting across an obstacle from one of its corners to another
+        !settings
+            .tiles_overlapping(Rectangle::from_corners(line.start, line.end))
+            .flat_map(|tile| &self.boundary[tile])
+            .any(|edge| segments_touch(line, edge))
+            && self.contains(line.parametric_point(0.5))
+    }
+
+    fn contains(&self, point: Vec2) -> bool {
+        let Some(settings) = &self.settings else { return false; };
+        settings
+            .tiles_overlapping(Rectangle::from_corners(point, point))
+            .flat_map(|tile| self.tile_triangles[tile].clone())
+            .any(|triangle| {
+                self.closest_point_on_triangle(triangle, point)
+                    .distance_squared(point)
+                    <= f32::EPSILON
+            })
+    }
+
+    fn closest_point_on_triangle(&self, triangle: usize, point: Vec2) -> Vec2 {
+        let [a, b, c] = self.triangles[triangle];
+        closest_point_on_triangle(
+            [self.vertices[a], self.vertices[b], self.vertices[c]],
+            point,
+        )
+    }
+
+    fn centroid(&self, triangle: usize) -> Vec2 {
+        let [a, b, c] = self.triangles[triangle];
+        (self.vertices[a] + self.vertices[b] + self.vertices[c]) / 3.0
+    }
+
+    /// The triangle containing `point`, or else the closest triangle to it, and the closest point on that
+    /// triangle.
+    fn locate(&self, point: Vec2) -> Option<(usize, Vec2)> {
+        let settings = self.settings.as_ref()?;
+        let (columns, _) = settings.grid_size();
+        let mut tiles: Vec<(f32, usize)> = (0..self.tile_triangles.len())
+            .map(|tile| {
+                let rect = settings.tile_rect(tile % columns, tile / columns);
+                (rect.distance_to_point(point), tile)
+            })
+            .collect();
+        tiles.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+
+        // Search the tiles from the closest outwards, until they're all further away than the closest triangle
+        let mut closest: Option<(usize, Vec2, f32)> = None;
+        for (distance, tile) in tiles {
+            if closest.is_some_and(|(.., closest)| distance > closest) {
+                break;
+            }
+
+            for triangle in self.tile_triangles[tile].clone() {
+                let on_triangle = self.closest_point_on_triangle(triangle, point);
+                let distance = on_triangle.distance(point);
+                if closest.is_none_or(|(.., closest)| distance < closest) {
+                    closest = Some((triangle, on_triangle, distance));
+                }
+            }
+        }
+
+        closest.map(|(triangle, on_triangle, _)| (triangle, on_triangle))
+    }
+
+    /// The triangles that an A* search across the navmesh passes through from `start` to `goal`.
+    fn find_corridor(&self, start: usize, goal: usize, goal_point: Vec2) -> Option<Vec<usize>> {
+        let mut costs = HashMap::from([(start, 0.0)]);
+        let mut came_from = HashMap::new();
+        let mut open = BinaryHeap::from([Candidate {
+            triangle: start,
+            estimate: 0.0,
+        }]);
+
+        while let Some(Candidate { triangle, .. }) = open.pop() {
+            if triangle == goal {
+                let mut corridor = vec![goal];
+                while let Some(&previous) = came_from.get(corridor.last().unwrap()) {
+                    corridor.push(previous);
+                }
+                corridor.reverse();
+                return Some(corridor);
+            }
+
+            let cost = costs[&triangle];
+            for neighbour in self.neighbours[triangle].into_iter().flatten() {
+                let neighbour_cost =
+                    cost + self.centroid(triangle).distance(self.centroid(neighbour));
+                if costs
+                    .get(&neighbour)
+                    .is_none_or(|&previous| neighbour_cost < previous)
+                {
+                    costs.insert(neighbour, neighbour_cost);
+                    came_from.insert(neighbour, triangle);
+                    open.push(Candidate {
+                        triangle: neighbour,
+                        estimate: neighbour_cost + self.centroid(neighbour).distance(goal_point),
+                    });
+                }
+            }
+        }
+
+        None
+    }
+
+    /// The left and right ends of the edge shared by two neighbouring triangles, as seen when crossing it from
+    /// `from` into `to`.
+    fn portal(&self, from: usize, to: usize) -> (Vec2, Vec2) {
+        let edge = self.neighbours[from]
+            .iter()
+            .position(|&neighbour| neighbour == Some(to))
+            .unwrap();
+        let triangle = self.triangles[from];
+
+        // Triangles are anticlockwise, so their edges run from right to left when seen from inside
+        (
+            self.vertices[triangle[(edge + 1) % 3]],
+            self.vertices[triangle[edge]],
+        )
+    }
+
+    /// Joins the triangles of every tile back up into a single mesh.
+    fn rebuild(&mut self) {
+        let mut indices = HashMap::new();
+        self.vertices.clear();
+        self.triangles.clear();
+        self.tile_triangles.clear();
+
+        for tile in &self.tiles {
+            let start = self.triangles.len();
+            for triangle in tile {
+                // Tiles subdivide their shared borders identically, so their vertices match exactly
+                self.triangles.push(triangle.map(|vertex| {
+                    *indices
+                        .entry((vertex.x.to_bits(), vertex.y.to_bits()))
+                        .or_insert_with(|| {
+                            self.vertices.push(vertex);
+                            self.vertices.len() - 1
+                        })
+                }));
+            }
+            self.tile_triangles.push(start..self.triangles.len());
+        }
+
+        let mut edges = HashMap::new();
+        self.neighbours = vec![[None; 3]; self.triangles.len()];
+        for (index, triangle) in self.triangles.iter().enumerate() {
+            for edge in 0..3 {
+                let (start, end) = (triangle[edge], triangle[(edge + 1) % 3]);
+                if let Some((other, other_edge)) = edges.remove(&(end, start)) {
+                    self.neighbours[index][edge] = Some(other);
+                    self.neighbours[other][other_edge] = Some(index);
+                } else {
+                    edges.insert((start, end), (index, edge));
+                }
+            }
+        }
+
+        self.boundary = vec![Vec::new(); self.tiles.len()];
+        for ((start, end), (triangle, _)) in edges {
+            let tile = self
+                .tile_triangles
+                .partition_point(|triangles| triangles.end <= triangle);
+            self.boundary[tile].push(Line::new(self.vertices[start], self.vertices[end]));
+        }
+    }
+}
+
+/// A triangle waiting to be explored by [`NavMesh::find_corridor`], ordered so that the lowest estimate is
+/// explored first.
+struct Candidate {
+    triangle: usize,
+    estimate: f32,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.total_cmp(&self.estimate)
+    }
+}
+
+/// Grows `obstacle` by the agent radius, and subdivides its outline to the maximum edge length.
+fn inflate(obstacle: &Collider, settings: &NavMeshSettings) -> Vec<Polygon> {
+    let outline = match obstacle.outline() {
+        // Points have no area to offset, so grow them into circles instead
+        outline if outline.len() < 3 => {
+            if settings.agent_radius <= 0.0 {
+                return Vec::new();
+            }
+            Collider::from(Circle::new(outline[0], settings.agent_radius)).outline()
+        }
+        outline => outline,
+    };
+
+    // Edges far longer than the bounds would otherwise be split into any number of points, almost all of which
+    // lie outside of the bounds
+    let max_edge_length = settings.limited_max_edge_length();
+    let max_segments = (settings.bounds.size().length() / max_edge_length).ceil();
+
+    let join_style = JoinStyle::Round(settings.agent_radius * ARC_TOLERANCE);
+    Polygon::new(outline)
+        .offset(settings.agent_radius.max(0.0), join_style)
+        .into_iter()
+        .map(|polygon| {
+            Polygon::new(
+                polygon
+                    .edges()
+                    .flat_map(|edge| {
+                        let max_length = max_edge_length.max(edge.length() / max_segments);
+                        subdivide(edge, max_length).skip(1)
+                    })
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+/// Evenly spaced points along `line` from its start to its end, inclusive, no further than `max_length` apart.
+fn subdivide(line: Line, max_length: f32) -> impl Iterator<Item = Vec2> {
+    let segments = (line.length() / max_length).ceil().max(1.0) as usize;
+    (0..=segments).map(move |i| line.parametric_point(i as f32 / segments as f32))
+}
+
+fn polygon_aabb(polygon: &Polygon) -> Rectangle {
+    let min = polygon.vertices.iter().copied().reduce(Vec2::min);
+    let max = polygon.vertices.iter().copied().reduce(Vec2::max);
+    Rectangle::from_corners(min.unwrap_or_default(), max.unwrap_or_default())
+}
+
+/// Triangulates the parts of `tile` that lie outside of every inflated obstacle.
+fn triangulate_tile(
+    tile: Rectangle,
+    obstacles: &[(Rectangle, Polygon)],
+    max_edge_length: f32,
+) -> Vec<[Vec2; 3]> {
+    let obstacles: Vec<&Polygon> = obstacles
+        .iter()
+        .filter(|(aabb, _)| aabb.intersects(&tile))
+        .map(|(_, polygon)| polygon)
+        .collect();
+
+    // Always walk the borders from their minimum to their maximum, so that neighbouring tiles agree on them
+    let (min, max) = (tile.min(), tile.max());
+    let borders = [
+        Line::new(min, Vec2::new(max.x, min.y)),
+        Line::new(Vec2::new(min.x, max.y), max),
+        Line::new(min, Vec2::new(min.x, max.y)),
+        Line::new(Vec2::new(max.x, min.y), max),
+    ];
+    let mut points: Vec<Vec2> = borders
+        .into_iter()
+        .flat_map(|border| subdivide(border, max_edge_length))
+        .collect();
+
+    // Where obstacles cross the borders, so that triangles can follow them right up to the edge of the tile.
+    // Neighbouring tiles find exactly the same crossings, as long as they're snapped onto the border
+    let crossings: Vec<Vec2> = borders
+        .iter()
+        .flat_map(|border| {
+            obstacles
+                .iter()
+                .flat_map(|obstacle| obstacle.edges())
+                .filter_map(move |edge| match border.intersect_line(&edge) {
+                    LineIntersection::Intersecting(point) if border.is_vertical() => {
+                        Some(Vec2::new(border.start.x, point.y))
+                    }
+                    LineIntersection::Intersecting(point) => {
+                        Some(Vec2::new(point.x, border.start.y))
+                    }
+                    _ => None,
+                })
+        })
+        .collect();
+    points.extend(crossings);
+
+    // Obstacle vertices that are too close to the border would stop it from matching up with the next tile
+    let interior = tile.inset(-max_edge_length * 0.01);
+    points.extend(
+        obstacles
+            .iter()
+            .flat_map(|obstacle| obstacle.vertices.iter().copied())
+            .filter(|&vertex| interior.contains(vertex)),
+    );
+
+    let blocked = |point: Vec2| obstacles.iter().any(|obstacle| obstacle.contains(point));
+
+    // Fill in the rest of the tile with a triangular lattice, so that the triangles are well shaped enough for
+    // searches across their centroids to find corridors close to the shortest path
+    let row_height = max_edge_length * 3f32.sqrt() / 2.0;
+    for row in 1.. {
+        let y = min.y + (row as f32 * row_height);
+        if y > max.y - (row_height / 2.0) {
+            break;
+        }
+
+        let offset = if row % 2 == 0 { 1.0 } else { 0.5 };
+        points.extend(
+            (0..)
+                .map(|column| min.x + ((column as f32 + offset) * max_edge_length))
+                .take_while(|&x| x <= max.x - (max_edge_length / 2.0))
+                .map(|x| Vec2::new(x, y))
+                .filter(|&point| !blocked(point)),
+        );
+    }
+
+    points.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    points.dedup();
+
+    let crosses_obstacle = |line: &Line| {
+        obstacles
+            .iter()
+            .flat_map(|obstacle| obstacle.edges())
+            .any(|edge| segments_cross(line, &edge))
+    };
+
+    delaunay(&points)
+        .into_iter()
+        .map(|triangle| triangle.map(|index| points[index]))
+        .filter(|&[a, b, c]| (b - a).perp_dot(c - a) > f32::EPSILON)
+        .filter(|&[a, b, c]| {
+            let edges = [Line::new(a, b), Line::new(b, c), Line::new(c, a)];
+            !blocked((a + b + c) / 3.0) && !edges.iter().any(crosses_obstacle)
+        })
+        .collect()
+}
+
+/// Whether `a` and `b` cross each other, not counting when they only touch.
+fn segments_cross(a: &Line, b: &Line) -> bool {
+    let side = |line: &Line, point: Vec2| line.as_difference().perp_dot(point - line.start);
+    let (b_start, b_end) = (side(a, b.start), side(a, b.end));
+    let (a_start, a_end) = (side(b, a.start), side(b, a.end));
+    (b_start * b_end < 0.0) && (a_start * a_end < 0.0)
+}
+
+/// Whether `a` crosses or touches `b` anywhere other than at the ends of `a`, not counting when they run along
+/// each other.
+fn segments_touch(a: &Line, b: &Line) -> bool {
+    let side = |line: &Line, point: Vec2| line.as_difference().perp_dot(point - line.start);
+    let (a_start, a_end) = (side(b, a.start), side(b, a.end));
+    let (b_start, b_end) = (side(a, b.start), side(a, b.end));
+    (a_start * a_end < 0.0) && (b_start * b_end <= 0.0)
+}
+
+/// The Delaunay triangulation of `points`, as anticlockwise indices into them, using the Bowyer-Watson
+/// algorithm.
+fn delaunay(points: &[Vec2]) -> Vec<[usize; 3]> {
+    if points.len() < 3 {
+        return Vec::new();
+    }
+
+    // Work in double precision, as the circumcircles of thin triangles are very sensitive to rounding
+    let mut vertices: Vec<DVec2> = points.iter().map(|point| point.as_dvec2()).collect();
+    let min = vertices.iter().copied().reduce(DVec2::min).unwrap();
+    let max = vertices.iter().copied().reduce(DVec2::max).unwrap();
+    let center = (min + max) / 2.0;
+    let extent = (max - min).max_element().max(1.0) * 100.0;
+
+    // A triangle that encloses every point, which is removed again at the end
+    let first_super = vertices.len();
+    vertices.extend([
+        center + DVec2::new(-extent, -extent),
+        center + DVec2::new(extent, -extent),
+        center + DVec2::new(0.0, extent),
+    ]);
+    let mut triangles = vec![[first_super, first_super + 1, first_super + 2]];
+
+    for (index, &point) in vertices[..first_super].iter().enumerate() {
+        let (bad, good): (Vec<[usize; 3]>, Vec<[usize; 3]>) = triangles
+            .into_iter()
+            .partition(|&triangle| in_circumcircle(triangle.map(|i| vertices[i]), point));
+        triangles = good;
+
+        // The edges around the hole left by the bad triangles, which are the ones that aren't shared
+        let mut boundary = HashSet::new();
+        for [a, b, c] in bad {
+            for (start, end) in [(a, b), (b, c), (c, a)] {
+                if !boundary.remove(&(end, start)) {
+                    boundary.insert((start, end));
+                }
+            }
+        }
+
+        triangles.extend(boundary.into_iter().map(|(start, end)| [start, end, index]));
+    }
+
+    triangles.retain(|triangle| triangle.iter().all(|&index| index < first_super));
+    triangles
+}
+
+/// Whether `point` lies inside the circumcircle of the anticlockwise triangle `[a, b, c]`.
+fn in_circumcircle([a, b, c]: [DVec2; 3], point: DVec2) -> bool {
+    let (a, b, c) = (a - point, b - point, c - point);
+    let determinant = (a.length_squared() * b.perp_dot(c)) - (b.length_squared() * a.perp_dot(c))
+        + (c.length_squared() * a.perp_dot(b));
+    determinant > 0.0
+}
+
+fn closest_point_on_triangle(vertices: [Vec2; 3], point: Vec2) -> Vec2 {
+    let [a, b, c] = vertices;
+    let inside = [(a, b), (b, c), (c, a)]
+        .iter()
+        .all(|(start, end)| (*end - *start).perp_dot(point - *start) >= 0.0);
+    if inside {
+        return point;
+    }
+
+    [Line::new(a, b), Line::new(b, c), Line::new(c, a)]
+        .iter()
+        .map(|edge| edge.closest_point_to_point(&point))
+        .min_by(|a, b| {
+            a.distance_squared(point)
+                .total_cmp(&b.distance_squared(point))
+        })
+        .unwrap()
+}
+
+/// Pulls the path through a corridor of `portals` taut, using the simple stupid funnel algorithm.
+fn funnel(start: Vec2, goal: Vec2, portals: &[(Vec2, Vec2)]) -> Vec<Vec2> {
+    let portals: Vec<(Vec2, Vec2)> = std::iter::once((start, start))
+        .chain(portals.iter().copied())
+        .chain(std::iter::once((goal, goal)))
+        .collect();
+
+    let mut path = vec![start];
+    let (mut apex, mut left, mut right) = (start, start, start);
+    let (mut left_index, mut right_index) = (0, 0);
+
+    let mut i = 1;
+    while i < portals.len() {
+        let (new_left, new_right) = portals[i];
+
+        // Narrow the funnel from the right, unless that would cross over the left side. Sides that pass through
+        // the apex don't constrain the funnel at all
+        if (right - apex).perp_dot(new_right - apex) >= 0.0 {
+            if new_right == apex || left == apex || (left - apex).perp_dot(new_right - apex) < 0.0 {
+                right = new_right;
+                right_index = i;
+            } else {
+                // The funnel has closed, so its left side becomes a corner of the path
+                path.push(left);
+                apex = left;
+                (right, right_index) = (left, left_index);
+                i = left_index + 1;
+                continue;
+            }
+        }
+
+        // Narrow the funnel from the left, unless that would cross over the right side
+        if (left - apex).perp_dot(new_left - apex) <= 0.0 {
+            if new_left == apex || right == apex || (right - apex).perp_dot(new_left - apex) > 0.0 {
+                left = new_left;
+                left_index = i;
+            } else {
+                path.push(right);
+                apex = right;
+                (left, left_index) = (right, right_index);
+                i = right_index + 1;
+                continue;
+            }
+        }
+
+        i += 1;
+    }
+
+    if path.last() != Some(&goal) {
+        path.push(goal);
+    }
+    path
+}
+
+type ObstacleQuery<'a> = (
+    Entity,
+    &'a GlobalTransform,
+    &'a Collider,
+    Option<&'a ColliderOffset>,
+    &'a CollisionLayers,
+);
+
+type ObstacleChanged = (
+    With<NavMeshObstacle>,
+    Or<(
+        Added<NavMeshObstacle>,
+        Changed<Collider>,
+        Changed<ColliderOffset>,
+        Changed<CollisionLayers>,
+        Changed<GlobalTransform>,
+    )>,
+);
+
+pub(crate) fn update_navmesh(
+    settings: Option<Res<NavMeshSettings>>,
+    mut navmesh: ResMut<NavMesh>,
+    cache: Res<ColliderCache>,
+    obstacles: Query<ObstacleQuery, With<NavMeshObstacle>>,
+    changed: Query<Entity, ObstacleChanged>,
+    removed: RemovedComponents<NavMeshObstacle>,
+    mut regions: Local<ColliderRegions>,
+) {
+    let Some(settings) = settings else { return; };
+
+    // Obstacles that were despawned or are no longer obstacles only show up as removals
+    let unchanged = changed.is_empty() && removed.iter().next().is_none();
+    if unchanged && navmesh.settings() == Some(&settings) {
+        return;
+    }
+
+    let current: HashMap<Entity, Collider> = obstacles
+        .iter()
+        .filter(|(.., layers)| settings.layers.intersects(layers))
+        .map(|(entity, transform, collider, offset, _)| {
+            let world = cache.world(entity).cloned();
+            (
+                entity,
+                world.unwrap_or_else(|| collider.to_world(transform, offset)),
+            )
+        })
+        .collect();
+    let aabbs = current
+        .iter()
+        .map(|(&entity, collider)| (entity, collider.aabb()))
+        .collect();
+    let changed_regions = regions.update(aabbs, changed.iter());
+    let colliders: Vec<Collider> = current.into_values().collect();
+
+    if navmesh.settings() != Some(&settings) {
+        *navmesh = NavMesh::generate(settings.clone(), &colliders);
+    } else if !changed_regions.is_empty() {
+        navmesh.regenerate(&colliders, changed_regions);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::{add_collision_stage, ColliderBundle};
+
+    /// How far `path` comes to `rect` at its closest.
+    fn clearance(path: &[Vec2], rect: &Rectangle) -> f32 {
+        path.windows(2)
+            .flat_map(|segment| (0..=50).map(move |i| segment[0].lerp(segment[1], i as f32 / 50.0)))
+            .map(|point| rect.distance_to_point(point))
+            .fold(f32::INFINITY, f32::min)
+    }
+
+    #[test]
+    fn paths_go_straight_without_obstacles() {
+        let bounds = Rectangle::new(0.0, 0.0, 100.0, 100.0);
+        let navmesh = NavMesh::generate(NavMeshSettings::new(bounds, 2.0), &[]);
+        let path = navmesh
+            .find_path(Vec2::new(10.0, 10.0), Vec2::new(90.0, 60.0))
+            .unwrap();
+
+        assert_eq!(path, vec![Vec2::new(10.0, 10.0), Vec2::new(90.0, 60.0)]);
+    }
+
+    #[test]
+    fn paths_avoid_obstacles() {
+        let bounds = Rectangle::new(0.0, 0.0, 100.0, 100.0);
+        let wall = Rectangle::new(45.0, 0.0, 55.0, 80.0);
+        let navmesh = NavMesh::generate(NavMeshSettings::new(bounds, 2.0), &[wall.into()]);
+        let path = navmesh
+            .find_path(Vec2::new(10.0, 10.0), Vec2::new(90.0, 10.0))
+            .unwrap();
+
+        assert_eq!(path.first(), Some(&Vec2::new(10.0, 10.0)));
+        assert_eq!(path.last(), Some(&Vec2::new(90.0, 10.0)));
+        assert!(path.iter().any(|point| point.y > 80.0));
+        // Rounding the inflated corners off with chords can cut into the agent radius slightly
+        assert!(clearance(&path, &wall) > 2.0 * (1.0 - ARC_TOLERANCE));
+    }
+
+    #[test]
+    fn regenerates_around_moved_obstacles() {
+        let bounds = Rectangle::new(0.0, 0.0, 100.0, 100.0);
+        let before = Rectangle::new(45.0, 0.0, 55.0, 80.0);
+        let after = Rectangle::new(45.0, 20.0, 55.0, 100.0);
+        let mut navmesh = NavMesh::generate(NavMeshSettings::new(bounds, 2.0), &[before.into()]);
+        navmesh.regenerate(&[after.into()], [before, after]);
+        let path = navmesh
+            .find_path(Vec2::new(10.0, 90.0), Vec2::new(90.0, 90.0))
+            .unwrap();
+
+        assert!(path.iter().any(|point| point.y < 20.0));
+        assert!(clearance(&path, &after) > 2.0 * (1.0 - ARC_TOLERANCE));
+    }
+
+    #[test]
+    fn limits_sizes_relative_to_the_bounds() {
+        let settings = NavMeshSettings::new(Rectangle::new(0.0, 0.0, 64.0, 32.0), 1.0)
+            .with_tile_size(0.0)
+            .with_max_edge_length(-1.0);
+
+        assert_eq!(settings.grid_size(), (32, 16));
+        assert_eq!(settings.limited_max_edge_length(), 2.0 / 16.0);
+    }
+
+    #[test]
+    fn regenerates_around_despawned_obstacles() {
+        let mut app = App::new();
+        add_collision_stage(&mut app);
+        let bounds = Rectangle::new(0.0, 0.0, 100.0, 100.0);
+        app.insert_resource(NavMeshSettings::new(bounds, 2.0));
+        let wall = app
+            .world
+            .spawn((
+                ColliderBundle::rect(Rect::new(45.0, 0.0, 55.0, 80.0)),
+                NavMeshObstacle,
+            ))
+            .id();
+        app.update();
+
+        let (start, goal) = (Vec2::new(10.0, 10.0), Vec2::new(90.0, 10.0));
+        let path = app.world.resource::<NavMesh>().find_path(start, goal);
+        assert!(path.unwrap().len() > 2);
+
+        app.world.despawn(wall);
+        app.update();
+        let path = app.world.resource::<NavMesh>().find_path(start, goal);
+        assert_eq!(path, Some(vec![start, goal]));
+    }
+}
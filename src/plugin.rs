@@ -1,9 +1,13 @@
 use super::{
     collider::*,
     draw::DrawPlugin,
+    grid::{update_occupancy_grid, OccupancyGrid},
     layers::{CollisionLayers, CollisionLayersLabel},
+    navmesh::{update_navmesh, NavMesh},
+    query::{update_ray_casters, ColliderCache},
+    view::{update_colliders_in_view, ViewEvent},
 };
-use bevy::prelude::*;
+use bevy::{ecs::system::SystemChangeTick, prelude::*, utils::HashMap};
 use std::ops::Neg;
 
 #[derive(StageLabel)]
@@ -12,16 +16,38 @@ pub struct CollisionStage;
 #[derive(SystemLabel)]
 pub struct FindCollidingPairs;
 
+#[derive(SystemLabel)]
+pub struct UpdateRayCasters;
+
+#[derive(SystemLabel)]
+pub struct UpdateCollidersInView;
+
+#[derive(SystemLabel)]
+pub struct UpdateNavMesh;
+
+#[derive(SystemLabel)]
+pub struct UpdateOccupancyGrid;
+
 pub struct CollisionPlugin;
 
 impl Plugin for CollisionPlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<CollisionEvent>();
+        add_collision_stage(app);
 
         #[cfg(feature = "debug-draw")]
         app.add_plugin(DrawPlugin);
+    }
+}
 
-        app.add_stage_after(
+/// Adds everything that [`CollisionPlugin`] does apart from debug drawing, which needs a renderer.
+pub(crate) fn add_collision_stage(app: &mut App) {
+    app.add_event::<CollisionEvent>()
+        .add_event::<ViewEvent>()
+        .init_resource::<ContactSkin>()
+        .init_resource::<ColliderCache>()
+        .init_resource::<NavMesh>()
+        .init_resource::<OccupancyGrid>()
+        .add_stage_after(
             CoreStage::Update,
             CollisionStage,
             SystemStage::single_threaded(),
@@ -29,8 +55,31 @@ impl Plugin for CollisionPlugin {
         .add_system_to_stage(
             CollisionStage,
             find_colliding_pairs.label(FindCollidingPairs),
+        )
+        .add_system_to_stage(
+            CollisionStage,
+            update_ray_casters
+                .label(UpdateRayCasters)
+                .after(FindCollidingPairs),
+        )
+        .add_system_to_stage(
+            CollisionStage,
+            update_colliders_in_view
+                .label(UpdateCollidersInView)
+                .after(FindCollidingPairs),
+        )
+        .add_system_to_stage(
+            CollisionStage,
+            update_navmesh
+                .label(UpdateNavMesh)
+                .after(FindCollidingPairs),
+        )
+        .add_system_to_stage(
+            CollisionStage,
+            update_occupancy_grid
+                .label(UpdateOccupancyGrid)
+                .after(FindCollidingPairs),
         );
-    }
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -41,7 +90,22 @@ pub enum CollisionEvent {
 
 impl CollisionEvent {
     pub fn began(us: Entity, them: Entity, contact: ContactManifold) -> Self {
-        Self::Began(CollisionBegan { us, them, contact })
+        Self::Began(CollisionBegan {
+            us,
+            them,
+            contact,
+            time_of_impact: None,
+        })
+    }
+
+    /// A collision that was found by sweeping a collider with [`Ccd`] enabled.
+    pub fn began_at(us: Entity, them: Entity, time_of_impact: TimeOfImpact) -> Self {
+        Self::Began(CollisionBegan {
+            us,
+            them,
+            contact: time_of_impact.contact,
+            time_of_impact: Some(time_of_impact.time),
+        })
     }
 
     pub fn ended(us: Entity, them: Entity) -> Self {
@@ -53,7 +117,11 @@ impl CollisionEvent {
 pub struct CollisionBegan {
     pub us: Entity,
     pub them: Entity,
+    /// The contact at the time of impact.
     pub contact: ContactManifold,
+    /// When the colliders first touched during the frame, from 0 at their previous transforms to 1 at their
+    /// current ones. Only available when at least one of them has [`Ccd`] enabled.
+    pub time_of_impact: Option<f32>,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -65,6 +133,7 @@ pub struct CollisionEnded {
 #[derive(Bundle, Default, Debug)]
 pub struct ColliderBundle {
     pub collider: Collider,
+    pub offset: ColliderOffset,
     pub colliding: Colliding,
     pub layers: CollisionLayers,
     pub transform: TransformBundle,
@@ -125,6 +194,12 @@ impl ColliderBundle {
     //     }
     // }
 
+    /// Offsets the collider relative to the entity, see [`ColliderOffset`]
+    pub fn with_offset(mut self, translation: Vec2, rotation: f32) -> Self {
+        self.offset = ColliderOffset::new(translation, rotation);
+        self
+    }
+
     /// Specifies that this collider will only interact with other colliders that have matching layers,
     /// including colliders that have no specified layer
     pub fn with_layers_inclusive(mut self, layers: impl CollisionLayersLabel) -> Self {
@@ -140,39 +215,163 @@ impl ColliderBundle {
     }
 }
 
+/// Remembers where colliders were from one frame to the next, to find the regions that need updating when they
+/// change.
+#[derive(Default)]
+pub(crate) struct ColliderRegions(HashMap<Entity, Rectangle>);
+
+impl ColliderRegions {
+    /// The regions that `changed` colliders covered before and after they changed, as well as those covered by
+    /// colliders that are no longer `current`. Remembers `current`, the bounding boxes of the colliders this
+    /// frame, for next time.
+    pub(crate) fn update(
+        &mut self,
+        current: HashMap<Entity, Rectangle>,
+        changed: impl IntoIterator<Item = Entity>,
+    ) -> Vec<Rectangle> {
+        let mut regions: Vec<Rectangle> = changed
+            .into_iter()
+            .flat_map(|entity| [self.0.get(&entity), current.get(&entity)])
+            .flatten()
+            .copied()
+            .collect();
+        regions.extend(
+            self.0
+                .iter()
+                .filter(|(entity, _)| !current.contains_key(*entity))
+                .map(|(_, &aabb)| aabb),
+        );
+
+        self.0 = current;
+        regions
+    }
+}
+
+/// The components of every collider that [`find_colliding_pairs`] tests against each other.
+type CollidingPairsQuery<'a> = (
+    Entity,
+    &'a GlobalTransform,
+    &'a Collider,
+    Option<&'a ColliderOffset>,
+    Option<&'a ContactSkin>,
+    Option<&'a mut Ccd>,
+    &'a CollisionLayers,
+    &'a mut Colliding,
+);
+
 fn find_colliding_pairs(
     mut commands: Commands,
-    mut query: Query<(
-        Entity,
-        &GlobalTransform,
-        &Collider,
-        &CollisionLayers,
-        &mut Colliding,
-    )>,
+    mut query: Query<CollidingPairsQuery>,
+    changed: Query<(), Changed<Collider>>,
+    default_skin: Res<ContactSkin>,
+    mut cache: ResMut<ColliderCache>,
     mut events: EventWriter<CollisionEvent>,
+    ticks: SystemChangeTick,
 ) {
     let mut events_batch = Vec::new();
 
+    // Move every collider that changed or moved into world space up-front, rather than once per pair, and keep
+    // them around for any spatial queries later on
+    let colliders = query
+        .iter()
+        .map(|(entity, transform, collider, offset, ..)| {
+            let offset = offset.copied().unwrap_or_default();
+            let changed = changed.contains(entity);
+            (entity, collider, changed, offset.apply(transform))
+        });
+    cache.update(colliders, ticks.change_tick());
+    let skins: HashMap<Entity, f32> = query
+        .iter()
+        .map(|(entity, _, _, _, skin, ..)| (entity, skin.unwrap_or(&default_skin).0))
+        .collect();
+
+    // The world space motion of every collider with CCD enabled since the previous frame
+    let sweeps: HashMap<Entity, Sweep> = query
+        .iter()
+        .filter_map(|(entity, transform, _, offset, _, ccd, ..)| {
+            let previous = ccd?.previous.unwrap_or(*transform);
+            let offset = offset.copied().unwrap_or_default();
+            Some((
+                entity,
+                Sweep::new(offset.apply(&previous), offset.apply(transform)),
+            ))
+        })
+        .collect();
+
     let mut iter = query.iter_combinations_mut();
     while let Some([us, them]) = iter.fetch_next() {
-        let (us_entity, us_transform, us_collider, us_layers, mut us_colliding) = us;
-        let (them_entity, them_transform, them_collider, them_layers, mut them_colliding) = them;
+        let (us_entity, us_transform, us_local, us_offset, _, _, us_layers, mut us_colliding) = us;
+        let (
+            them_entity,
+            them_transform,
+            them_local,
+            them_offset,
+            _,
+            _,
+            them_layers,
+            mut them_colliding,
+        ) = them;
 
         if !us_layers.intersects(them_layers) {
             continue;
         }
 
-        let contact = us_collider
-            .to_transformed(us_transform)
-            .collide(&them_collider.to_transformed(them_transform));
+        let Some(us_collider) = cache.world(us_entity) else { continue; };
+        let Some(them_collider) = cache.world(them_entity) else { continue; };
+        let margin = skins[&us_entity] + skins[&them_entity];
+
+        // Always collide each pair the same way around, regardless of the order the query visits them in, so
+        // that the manifold and its feature IDs stay stable from one frame to the next
+        let contact = if us_entity < them_entity {
+            us_collider.collide_with_margin(them_collider, margin)
+        } else {
+            them_collider
+                .collide_with_margin(us_collider, margin)
+                .map(|manifold| -manifold)
+        };
+
+        // Sweep pairs that weren't already colliding if either of them has CCD enabled, to find out when they
+        // first touched, and to catch fast colliders that passed straight through each other
+        let swept = if (sweeps.contains_key(&us_entity) || sweeps.contains_key(&them_entity))
+            && !us_colliding.0.contains_key(&them_entity)
+        {
+            let sweep =
+                |entity: Entity, transform: &GlobalTransform, offset: Option<&ColliderOffset>| {
+                    sweeps.get(&entity).copied().unwrap_or_else(|| {
+                        Sweep::stationary(offset.copied().unwrap_or_default().apply(transform))
+                    })
+                };
+
+            let us_sweep = sweep(us_entity, us_transform, us_offset);
+            let them_sweep = sweep(them_entity, them_transform, them_offset);
+            if us_entity < them_entity {
+                us_local.time_of_impact(&us_sweep, them_local, &them_sweep)
+            } else {
+                them_local
+                    .time_of_impact(&them_sweep, us_local, &us_sweep)
+                    .map(|toi| TimeOfImpact {
+                        time: toi.time,
+                        contact: -toi.contact,
+                    })
+            }
+        } else {
+            None
+        };
 
         if let Some(manifold) = contact {
             let us_was_disjoint = us_colliding.0.insert(them_entity, manifold).is_none();
             let them_was_disjoint = them_colliding.0.insert(us_entity, manifold.neg()).is_none();
             if us_was_disjoint && them_was_disjoint {
                 // Only send a collision event if neither entity was already colliding with the other
-                events_batch.push(CollisionEvent::began(us_entity, them_entity, manifold));
+                events_batch.push(match swept {
+                    Some(toi) => CollisionEvent::began_at(us_entity, them_entity, toi),
+                    None => CollisionEvent::began(us_entity, them_entity, manifold),
+                });
             }
+        } else if let Some(toi) = swept {
+            // The colliders touched during the frame, but have already moved apart again
+            events_batch.push(CollisionEvent::began_at(us_entity, them_entity, toi));
+            events_batch.push(CollisionEvent::ended(us_entity, them_entity));
         } else {
             let us_was_colliding = us_colliding.0.remove(&them_entity).is_some();
             let them_was_colliding = them_colliding.0.remove(&us_entity).is_some();
@@ -183,5 +382,11 @@ fn find_colliding_pairs(
         }
     }
 
+    for (_, transform, _, _, _, ccd, ..) in query.iter_mut() {
+        if let Some(mut ccd) = ccd {
+            ccd.previous = Some(*transform);
+        }
+    }
+
     events.send_batch(events_batch);
 }
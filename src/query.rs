@@ -0,0 +1,724 @@
+use super::{
+    collider::*,
+    layers::{CollisionLayerFlags, CollisionLayers},
+    sdf::SignedDistanceField,
+};
+use crate::transform_ext::TransformPoint2;
+use bevy::{
+    ecs::system::{SystemChangeTick, SystemParam},
+    prelude::*,
+    tasks::{ComputeTaskPool, TaskPool},
+    utils::{HashMap, HashSet},
+};
+use std::borrow::Cow;
+
+/// Decides which colliders a [`SpatialQuery`] considers.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueryFilter {
+    /// Only colliders whose layers intersect these layers are considered, as if the query were a collider
+    /// itself. By default, every collider is considered.
+    pub layers: CollisionLayers,
+    /// Colliders on these entities are never considered.
+    pub excluded: HashSet<Entity>,
+}
+
+impl QueryFilter {
+    pub fn new(layers: CollisionLayers) -> Self {
+        Self {
+            layers,
+            excluded: HashSet::default(),
+        }
+    }
+
+    pub fn with_layers(mut self, layers: CollisionLayers) -> Self {
+        self.layers = layers;
+        self
+    }
+
+    /// Ignores the colliders on the given entities, such as the entity performing the query.
+    pub fn exclude(mut self, entities: impl IntoIterator<Item = Entity>) -> Self {
+        self.excluded.extend(entities);
+        self
+    }
+
+    pub fn allows(&self, entity: Entity, layers: &CollisionLayers) -> bool {
+        !self.excluded.contains(&entity) && self.layers.intersects(layers)
+    }
+}
+
+impl Default for QueryFilter {
+    fn default() -> Self {
+        Self::new(CollisionLayers::Inclusive(CollisionLayerFlags::EVERYTHING))
+    }
+}
+
+/// Casts a ray from an entity every frame in the [`CollisionStage`](crate::plugin::CollisionStage), and stores
+/// what it hits in [`RayHits`].
+///
+/// The collider of the entity itself (if any) is never hit.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct RayCaster {
+    /// Where the ray starts, in the local space of the entity.
+    pub origin: Vec2,
+    /// Which way the ray points, in the local space of the entity.
+    pub direction: Vec2,
+    /// How far the ray reaches, in world space.
+    pub max_distance: f32,
+    pub layers: CollisionLayers,
+    /// The maximum number of hits to report, starting from the closest.
+    pub max_hits: usize,
+    /// Whether colliders that the ray starts inside are hit straight away, see [`Collider::cast_ray`].
+    pub solid: bool,
+}
+
+impl RayCaster {
+    pub fn new(origin: Vec2, direction: Vec2, max_distance: f32) -> Self {
+        Self {
+            origin,
+            direction,
+            max_distance,
+            layers: QueryFilter::default().layers,
+            max_hits: 1,
+            solid: true,
+        }
+    }
+
+    pub fn with_layers(mut self, layers: CollisionLayers) -> Self {
+        self.layers = layers;
+        self
+    }
+
+    pub fn with_max_hits(mut self, max_hits: usize) -> Self {
+        self.max_hits = max_hits;
+        self
+    }
+
+    pub fn with_solid(mut self, solid: bool) -> Self {
+        self.solid = solid;
+        self
+    }
+
+    /// The ray in world space, with a unit direction so that times of impact are distances.
+    pub fn to_world(&self, transform: &GlobalTransform) -> Ray2d {
+        Ray2d::new(
+            transform.transform_point2(self.origin),
+            transform.transform_vec2(self.direction).normalize_or_zero(),
+        )
+    }
+}
+
+/// Everything that the [`RayCaster`] of an entity hit in the last frame, ordered from closest to furthest.
+///
+/// Inserted automatically the first time the ray is cast.
+#[derive(Component, Clone, Default, Debug, PartialEq)]
+pub struct RayHits(pub Vec<(Entity, RayHit)>);
+
+/// Queries every collider in the world, for raycasts, shape casts and overlap tests.
+///
+/// Queries reuse the world space colliders from the last run of
+/// [`FindCollidingPairs`](crate::plugin::FindCollidingPairs), and only move colliders into world space again if
+/// they have moved or changed since.
+#[derive(SystemParam)]
+pub struct SpatialQuery<'w, 's> {
+    colliders: Query<'w, 's, SpatialQueryColliders>,
+    cache: Option<Res<'w, ColliderCache>>,
+    ticks: SystemChangeTick,
+}
+
+type SpatialQueryColliders = (
+    Entity,
+    &'static GlobalTransform,
+    &'static Collider,
+    ChangeTrackers<Collider>,
+    Option<&'static ColliderOffset>,
+    &'static CollisionLayers,
+);
+
+impl<'w, 's> SpatialQuery<'w, 's> {
+    /// Every collider that passes `filter`, moved into world space.
+    fn world_colliders(&self, filter: &QueryFilter) -> WorldColliders<'_> {
+        // Change detection is relative to the last run of the system doing the query, so it only covers every
+        // change since the cache was updated if the cache was updated after that
+        let cache = self.cache.as_ref().filter(|cache| {
+            let now = self.ticks.change_tick();
+            now.wrapping_sub(cache.tick) < now.wrapping_sub(self.ticks.last_change_tick())
+        });
+
+        WorldColliders(
+            self.colliders
+                .iter()
+                .filter(|(entity, .., layers)| filter.allows(*entity, layers))
+                .map(|(entity, transform, collider, tracker, offset, _)| {
+                    let transform = offset.copied().unwrap_or_default().apply(transform);
+                    let cached = cache
+                        .filter(|_| !tracker.is_changed())
+                        .and_then(|cache| cache.get(entity, &transform));
+
+                    match cached {
+                        Some((collider, aabb)) => (entity, Cow::Borrowed(collider), aabb),
+                        None => {
+                            let collider = collider.to_transformed(&transform);
+                            let aabb = collider.aabb();
+                            (entity, Cow::Owned(collider), aabb)
+                        }
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    /// The first collider that `ray` hits within `max_toi`, see [`Collider::cast_ray`].
+    pub fn cast_ray(
+        &self,
+        ray: &Ray2d,
+        max_toi: f32,
+        solid: bool,
+        filter: &QueryFilter,
+    ) -> Option<(Entity, RayHit)> {
+        self.world_colliders(filter).cast_ray(ray, max_toi, solid)
+    }
+
+    /// Like [`SpatialQuery::cast_ray`], but casts every ray in `rays` in parallel on the [`ComputeTaskPool`].
+    pub fn cast_rays(
+        &self,
+        rays: &[Ray2d],
+        max_toi: f32,
+        solid: bool,
+        filter: &QueryFilter,
+    ) -> Vec<Option<(Entity, RayHit)>> {
+        let colliders = self.world_colliders(filter);
+        par_map(rays, |ray| colliders.cast_ray(ray, max_toi, solid))
+    }
+
+    /// Every collider that `ray` hits within `max_toi`, ordered from closest to furthest and limited to
+    /// `max_hits`.
+    pub fn cast_ray_all(
+        &self,
+        ray: &Ray2d,
+        max_toi: f32,
+        solid: bool,
+        max_hits: usize,
+        filter: &QueryFilter,
+    ) -> Vec<(Entity, RayHit)> {
+        let mut hits: Vec<(Entity, RayHit)> = self
+            .world_colliders(filter)
+            .0
+            .iter()
+            .filter(|(_, _, aabb)| Shape::cast_ray(aabb, ray, max_toi, true).is_some())
+            .filter_map(|(entity, collider, _)| {
+                Some((
+                    *entity,
+                    Shape::cast_ray(collider.as_ref(), ray, max_toi, solid)?,
+                ))
+            })
+            .collect();
+
+        hits.sort_by(|(_, a), (_, b)| a.toi.total_cmp(&b.toi));
+        hits.truncate(max_hits);
+        hits
+    }
+
+    /// The first collider that `collider` hits when it's moved from `transform` by `velocity`, see
+    /// [`Collider::cast_shape`].
+    pub fn cast_shape(
+        &self,
+        collider: &Collider,
+        transform: &GlobalTransform,
+        velocity: Vec2,
+        filter: &QueryFilter,
+    ) -> Option<(Entity, ShapeHit)> {
+        // Only colliders near the path of the shape can be hit
+        let start = collider.to_transformed(transform).aabb();
+        let end = Rectangle::from_corners(start.min() + velocity, start.max() + velocity);
+        let swept = start.union(end);
+
+        self.world_colliders(filter)
+            .0
+            .iter()
+            .filter(|(_, _, aabb)| aabb.intersects(&swept))
+            .filter_map(|(entity, other, _)| {
+                let hit =
+                    collider.cast_shape(transform, velocity, other, &GlobalTransform::IDENTITY)?;
+                Some((*entity, hit))
+            })
+            .min_by(|(_, a), (_, b)| a.toi.total_cmp(&b.toi))
+    }
+
+    /// Every collider that contains `point`.
+    pub fn intersect_point(&self, point: Vec2, filter: &QueryFilter) -> Vec<Entity> {
+        self.world_colliders(filter).intersect_point(point)
+    }
+
+    /// Like [`SpatialQuery::intersect_point`], but tests every point in `points` in parallel on the
+    /// [`ComputeTaskPool`].
+    pub fn intersect_points(&self, points: &[Vec2], filter: &QueryFilter) -> Vec<Vec<Entity>> {
+        let colliders = self.world_colliders(filter);
+        par_map(points, |point| colliders.intersect_point(*point))
+    }
+
+    /// Every collider that overlaps `collider` at `transform`.
+    pub fn intersect_shape(
+        &self,
+        collider: &Collider,
+        transform: &GlobalTransform,
+        filter: &QueryFilter,
+    ) -> Vec<Entity> {
+        self.world_colliders(filter)
+            .contacts(&collider.to_transformed(transform))
+            .into_iter()
+            .map(|(entity, _)| entity)
+            .collect()
+    }
+
+    /// Like [`SpatialQuery::intersect_shape`], but tests every collider in `shapes` in parallel on the
+    /// [`ComputeTaskPool`].
+    pub fn intersect_shapes(
+        &self,
+        shapes: &[(Collider, GlobalTransform)],
+        filter: &QueryFilter,
+    ) -> Vec<Vec<Entity>> {
+        let colliders = self.world_colliders(filter);
+        par_map(shapes, |(collider, transform)| {
+            colliders
+                .contacts(&collider.to_transformed(transform))
+                .into_iter()
+                .map(|(entity, _)| entity)
+                .collect()
+        })
+    }
+
+    /// Every collider that overlaps `collider` at `transform`, as if it were an entity on the given layers,
+    /// along with the contact between them.
+    ///
+    /// The normal of each manifold points from `collider` towards the other collider. Unlike spawning a
+    /// [`ColliderBundle`](crate::plugin::ColliderBundle), the result is available immediately.
+    pub fn overlaps(
+        &self,
+        collider: &Collider,
+        transform: &GlobalTransform,
+        layers: CollisionLayers,
+    ) -> Vec<(Entity, ContactManifold)> {
+        self.world_colliders(&QueryFilter::new(layers))
+            .contacts(&collider.to_transformed(transform))
+    }
+
+    /// The `k` colliders on matching layers that are closest to `point` and no further than `max_distance` from
+    /// it, along with their distances, ordered from closest to furthest.
+    ///
+    /// Distances are measured to the outlines of the colliders, and are 0 for colliders that contain `point`.
+    pub fn nearest(
+        &self,
+        point: Vec2,
+        k: usize,
+        layers: CollisionLayers,
+        max_distance: f32,
+    ) -> Vec<(Entity, f32)> {
+        if k == 0 {
+            return Vec::new();
+        }
+
+        let colliders = self.world_colliders(&QueryFilter::new(layers));
+
+        // The distance to a bounding box is never more than the distance to the collider inside it, so visiting
+        // colliders in order of their bounding box distance means the search can stop early
+        let mut candidates: Vec<(f32, &Entity, &Collider)> = colliders
+            .0
+            .iter()
+            .map(|(entity, collider, aabb)| {
+                (
+                    aabb.closest_point(point).distance(point),
+                    entity,
+                    collider.as_ref(),
+                )
+            })
+            .filter(|(distance, ..)| *distance <= max_distance)
+            .collect();
+        candidates.sort_by(|(a, ..), (b, ..)| a.total_cmp(b));
+
+        let mut nearest: Vec<(Entity, f32)> = Vec::with_capacity(k + 1);
+        for (aabb_distance, entity, collider) in candidates {
+            if nearest.len() == k && nearest.last().is_some_and(|(_, d)| aabb_distance > *d) {
+                break;
+            }
+
+            let (closest, is_inside, _) = collider.project_point(point);
+            let distance = if is_inside {
+                0.0
+            } else {
+                closest.distance(point)
+            };
+
+            if distance <= max_distance {
+                let index = nearest.partition_point(|(_, d)| *d <= distance);
+                nearest.insert(index, (*entity, distance));
+                nearest.truncate(k);
+            }
+        }
+
+        nearest
+    }
+
+    /// Whether or not `b` can be seen from `a`, without any colliders that pass `filter` in the way.
+    ///
+    /// Exclude the observer and its target in `filter`, otherwise their own colliders block the line of sight.
+    pub fn has_line_of_sight(&self, a: Vec2, b: Vec2, filter: &QueryFilter) -> bool {
+        self.world_colliders(filter)
+            .cast_ray(&Ray2d::between(a, b), 1.0, true)
+            .is_none_or(|(_, hit)| hit.toi >= 1.0)
+    }
+
+    /// The region that is visible from `origin` within `range` on each axis, when colliders on matching layers
+    /// block the view, see [`visibility_polygon`].
+    pub fn visibility_polygon(&self, origin: Vec2, range: f32, layers: CollisionLayers) -> Polygon {
+        let bounds = Rectangle::from_center_half_size(origin, Vec2::splat(range));
+        let occluders: Vec<Collider> = self
+            .world_colliders(&QueryFilter::new(layers))
+            .0
+            .into_iter()
+            .filter(|(_, _, aabb)| aabb.intersects(&bounds))
+            .map(|(_, collider, _)| collider.into_owned())
+            .collect();
+
+        visibility_polygon(origin, &occluders, bounds)
+    }
+
+    /// Bakes the signed distance to colliders on matching layers every `cell_size` across `bounds`, see
+    /// [`SignedDistanceField::bake`].
+    pub fn bake_sdf(
+        &self,
+        bounds: Rectangle,
+        cell_size: f32,
+        layers: CollisionLayers,
+    ) -> SignedDistanceField {
+        let colliders: Vec<Collider> = self
+            .world_colliders(&QueryFilter::new(layers))
+            .0
+            .into_iter()
+            .map(|(_, collider, _)| collider.into_owned())
+            .collect();
+
+        SignedDistanceField::bake(bounds, cell_size, &colliders)
+    }
+
+    /// The collider whose outline is closest to `point`, along with the closest point on that outline and
+    /// whether or not `point` lies inside the collider, see [`Collider::project_point`].
+    pub fn project_point(&self, point: Vec2, filter: &QueryFilter) -> Option<(Entity, Vec2, bool)> {
+        self.world_colliders(filter)
+            .0
+            .iter()
+            .map(|(entity, collider, _)| {
+                let (closest, is_inside, _) = collider.project_point(point);
+                (*entity, closest, is_inside)
+            })
+            .min_by(|(_, a, _), (_, b, _)| {
+                a.distance_squared(point)
+                    .total_cmp(&b.distance_squared(point))
+            })
+    }
+}
+
+/// The world space colliders found by [`FindCollidingPairs`](crate::plugin::FindCollidingPairs), which
+/// [`SpatialQuery`] reuses rather than moving every collider into world space again.
+#[derive(Resource, Default)]
+pub struct ColliderCache {
+    colliders: HashMap<Entity, CachedCollider>,
+    /// The change tick of the system that last updated the cache.
+    tick: u32,
+}
+
+struct CachedCollider {
+    /// The transform (including its offset) that `world` was moved into world space by.
+    transform: GlobalTransform,
+    world: Collider,
+    aabb: Rectangle,
+}
+
+impl ColliderCache {
+    /// Caches the given local colliders in world space, along with whether they changed since the last update.
+    /// Only colliders that changed or moved are moved into world space again, and any others are forgotten.
+    pub(crate) fn update<'a>(
+        &mut self,
+        colliders: impl IntoIterator<Item = (Entity, &'a Collider, bool, GlobalTransform)>,
+        tick: u32,
+    ) {
+        let mut previous = std::mem::take(&mut self.colliders);
+        self.colliders = colliders
+            .into_iter()
+            .map(|(entity, local, changed, transform)| {
+                let cached = previous
+                    .remove(&entity)
+                    .filter(|cached| !changed && cached.transform == transform)
+                    .unwrap_or_else(|| {
+                        let world = local.to_transformed(&transform);
+                        let aabb = world.aabb();
+                        CachedCollider {
+                            transform,
+                            world,
+                            aabb,
+                        }
+                    });
+                (entity, cached)
+            })
+            .collect();
+        self.tick = tick;
+    }
+
+    /// The collider of `entity` in world space, as of the last update.
+    pub(crate) fn world(&self, entity: Entity) -> Option<&Collider> {
+        self.colliders.get(&entity).map(|cached| &cached.world)
+    }
+
+    /// The collider of `entity` in world space along with its bounding box, unless it has moved since the last
+    /// update. Whether the collider itself changed is up to the caller.
+    fn get(&self, entity: Entity, transform: &GlobalTransform) -> Option<(&Collider, Rectangle)> {
+        self.colliders
+            .get(&entity)
+            .filter(|cached| cached.transform == *transform)
+            .map(|cached| (&cached.world, cached.aabb))
+    }
+}
+
+/// A snapshot of colliders in world space, along with their bounding boxes, which is built once and then shared
+/// by every query in a batch.
+struct WorldColliders<'a>(Vec<(Entity, Cow<'a, Collider>, Rectangle)>);
+
+impl WorldColliders<'_> {
+    fn cast_ray(&self, ray: &Ray2d, max_toi: f32, solid: bool) -> Option<(Entity, RayHit)> {
+        let mut closest: Option<(Entity, RayHit)> = None;
+        for (entity, collider, aabb) in &self.0 {
+            let max_toi = closest.map_or(max_toi, |(_, hit)| hit.toi);
+            if Shape::cast_ray(aabb, ray, max_toi, true).is_none() {
+                continue;
+            }
+
+            if let Some(hit) = Shape::cast_ray(collider.as_ref(), ray, max_toi, solid) {
+                closest = Some((*entity, hit));
+            }
+        }
+
+        closest
+    }
+
+    fn intersect_point(&self, point: Vec2) -> Vec<Entity> {
+        self.0
+            .iter()
+            .filter(|(_, collider, aabb)| {
+                aabb.contains(point) && Shape::contains(collider.as_ref(), point)
+            })
+            .map(|(entity, ..)| *entity)
+            .collect()
+    }
+
+    /// Every collider that overlaps `collider`, which must already be in world space.
+    fn contacts(&self, collider: &Collider) -> Vec<(Entity, ContactManifold)> {
+        let aabb = collider.aabb();
+        self.0
+            .iter()
+            .filter(|(_, _, other_aabb)| aabb.intersects(other_aabb))
+            .filter_map(|(entity, other, _)| Some((*entity, collider.collide(other)?)))
+            .collect()
+    }
+}
+
+/// Maps every item in `items` with `f`, spreading the work across the [`ComputeTaskPool`].
+fn par_map<T: Sync, R: Send + 'static>(items: &[T], f: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    let pool = ComputeTaskPool::init(TaskPool::default);
+    let chunk_size = (items.len() / pool.thread_num()).max(1);
+    let f = &f;
+
+    // Results come back in the order that their tasks were spawned
+    pool.scope(|scope| {
+        for chunk in items.chunks(chunk_size) {
+            scope.spawn(async move { chunk.iter().map(f).collect::<Vec<_>>() });
+        }
+    })
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+pub(crate) fn update_ray_casters(
+    mut commands: Commands,
+    mut casters: Query<(Entity, &GlobalTransform, &RayCaster, Option<&mut RayHits>)>,
+    spatial_query: SpatialQuery,
+) {
+    for (entity, transform, caster, hits) in casters.iter_mut() {
+        let filter = QueryFilter::new(caster.layers).exclude([entity]);
+        let new_hits = spatial_query.cast_ray_all(
+            &caster.to_world(transform),
+            caster.max_distance,
+            caster.solid,
+            caster.max_hits,
+            &filter,
+        );
+
+        match hits {
+            // Avoid triggering change detection when nothing changed
+            Some(mut hits) if hits.0 != new_hits => hits.0 = new_hits,
+            Some(_) => {}
+            None => {
+                commands.entity(entity).insert(RayHits(new_hits));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::{add_collision_stage, ColliderBundle};
+    use bevy::ecs::system::SystemState;
+
+    fn spawn_circle(app: &mut App, x: f32) -> Entity {
+        app.world
+            .spawn(ColliderBundle {
+                transform: TransformBundle {
+                    local: Transform::from_xyz(x, 0.0, 0.0),
+                    global: Transform::from_xyz(x, 0.0, 0.0).into(),
+                },
+                ..ColliderBundle::circle(1.0)
+            })
+            .id()
+    }
+
+    fn cast_ray(app: &mut App, filter: &QueryFilter) -> Option<Entity> {
+        let mut state: SystemState<SpatialQuery> = SystemState::new(&mut app.world);
+        let ray = Ray2d::new(Vec2::new(-10.0, 0.0), Vec2::X);
+        state
+            .get_mut(&mut app.world)
+            .cast_ray(&ray, f32::INFINITY, true, filter)
+            .map(|(entity, _)| entity)
+    }
+
+    #[test]
+    fn filters_by_entity_and_layers() {
+        let mut app = App::new();
+        add_collision_stage(&mut app);
+        let near = spawn_circle(&mut app, 0.0);
+        let far = spawn_circle(&mut app, 5.0);
+        app.update();
+
+        assert_eq!(cast_ray(&mut app, &QueryFilter::default()), Some(near));
+        assert_eq!(
+            cast_ray(&mut app, &QueryFilter::default().exclude([near])),
+            Some(far)
+        );
+
+        let layers = CollisionLayers::Exclusive(CollisionLayerFlags::from_bits_truncate(1));
+        app.world.entity_mut(far).insert(layers);
+        let filter = QueryFilter::new(layers).exclude([near]);
+        assert_eq!(cast_ray(&mut app, &filter), Some(far));
+        assert_eq!(
+            cast_ray(
+                &mut app,
+                &QueryFilter::new(CollisionLayers::None).exclude([near])
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn sees_colliders_that_changed_since_the_collision_stage() {
+        let mut app = App::new();
+        add_collision_stage(&mut app);
+        let near = spawn_circle(&mut app, 0.0);
+        let far = spawn_circle(&mut app, 5.0);
+        app.update();
+
+        // Moved out of the way after the world space colliders were cached
+        *app.world.get_mut::<GlobalTransform>(near).unwrap() =
+            Transform::from_xyz(0.0, 10.0, 0.0).into();
+        assert_eq!(cast_ray(&mut app, &QueryFilter::default()), Some(far));
+
+        // Moved back, but shrunk out of the way instead
+        *app.world.get_mut::<GlobalTransform>(near).unwrap() = GlobalTransform::IDENTITY;
+        assert_eq!(cast_ray(&mut app, &QueryFilter::default()), Some(near));
+        *app.world.get_mut::<Collider>(near).unwrap() =
+            Circle::new(Vec2::new(0.0, 10.0), 1.0).into();
+        assert_eq!(cast_ray(&mut app, &QueryFilter::default()), Some(far));
+
+        // Spawned since the cache was last updated
+        let nearest = spawn_circle(&mut app, -5.0);
+        assert_eq!(cast_ray(&mut app, &QueryFilter::default()), Some(nearest));
+    }
+
+    #[test]
+    fn reuses_the_cache_until_colliders_change() {
+        let mut app = App::new();
+        add_collision_stage(&mut app);
+        let near = spawn_circle(&mut app, 0.0);
+        let far = spawn_circle(&mut app, 5.0);
+
+        // Keep the same system state throughout, so that change detection is relative to the last query
+        let mut state: SystemState<SpatialQuery> = SystemState::new(&mut app.world);
+        let ray = Ray2d::new(Vec2::new(-10.0, 0.0), Vec2::X);
+        let mut cast_ray = |world: &mut World| {
+            state
+                .get_mut(world)
+                .cast_ray(&ray, f32::INFINITY, true, &QueryFilter::default())
+                .map(|(entity, _)| entity)
+        };
+        assert_eq!(cast_ray(&mut app.world), Some(near));
+        app.update();
+
+        // Shrunk out of the way after the cache was updated
+        *app.world.get_mut::<Collider>(near).unwrap() =
+            Circle::new(Vec2::new(0.0, 10.0), 1.0).into();
+        assert_eq!(cast_ray(&mut app.world), Some(far));
+
+        // The cache is now older than the last query, so it's stale even though nothing changed since
+        assert_eq!(cast_ray(&mut app.world), Some(far));
+    }
+
+    #[test]
+    fn cast_shape_hits_the_first_collider_in_the_way() {
+        let mut app = App::new();
+        add_collision_stage(&mut app);
+        let near = spawn_circle(&mut app, 0.0);
+        spawn_circle(&mut app, 5.0);
+        app.update();
+
+        let mut state: SystemState<SpatialQuery> = SystemState::new(&mut app.world);
+        let query = state.get_mut(&mut app.world);
+        let ball = Collider::from(Circle::new(Vec2::ZERO, 0.5));
+        let start = GlobalTransform::from(Transform::from_xyz(-10.0, 0.0, 0.0));
+        let filter = QueryFilter::default();
+
+        let (entity, hit) = query
+            .cast_shape(&ball, &start, Vec2::new(20.0, 0.0), &filter)
+            .unwrap();
+        assert_eq!(entity, near);
+        assert!((hit.toi - (8.5 / 20.0)).abs() < 1e-3);
+
+        // Passing above both of them
+        let above = GlobalTransform::from(Transform::from_xyz(-10.0, 2.0, 0.0));
+        assert!(query
+            .cast_shape(&ball, &above, Vec2::new(20.0, 0.0), &filter)
+            .is_none());
+    }
+
+    #[test]
+    fn line_of_sight_between_colliders() {
+        let mut app = App::new();
+        add_collision_stage(&mut app);
+        let observer = spawn_circle(&mut app, 0.0);
+        let target = spawn_circle(&mut app, 5.0);
+        app.update();
+
+        let mut state: SystemState<SpatialQuery> = SystemState::new(&mut app.world);
+        let query = state.get_mut(&mut app.world);
+        let filter = QueryFilter::default().exclude([observer, target]);
+
+        // From the center of one collider to the center of the other
+        assert!(query.has_line_of_sight(Vec2::ZERO, Vec2::new(5.0, 0.0), &filter));
+        assert!(query.has_line_of_sight(Vec2::new(5.0, 0.0), Vec2::ZERO, &filter));
+
+        // Only the excluded colliders are ignored
+        let filter = QueryFilter::default().exclude([target]);
+        assert!(!query.has_line_of_sight(Vec2::ZERO, Vec2::new(5.0, 0.0), &filter));
+
+        // Through the collider in between
+        assert!(!query.has_line_of_sight(Vec2::new(-5.0, 0.0), Vec2::new(5.0, 0.0), &filter));
+        assert!(query.has_line_of_sight(Vec2::new(-5.0, 2.0), Vec2::new(5.0, 2.0), &filter));
+    }
+}
@@ -0,0 +1,202 @@
+use super::collider::*;
+use bevy::prelude::*;
+
+/// The most cells that the bounds are split into along each axis.
+const MAX_CELLS_PER_SIDE: f32 = 1024.0;
+
+/// The signed distance to the nearest collider, sampled on a regular grid, see [`SignedDistanceField::bake`].
+///
+/// Distances are negative inside colliders. They are baked at the corners of the cells, so that [`sample`] can
+/// interpolate them anywhere within the bounds.
+///
+/// [`sample`]: SignedDistanceField::sample
+#[derive(Resource, Clone, Default, Debug)]
+pub struct SignedDistanceField {
+    bounds: Rectangle,
+    cell_size: f32,
+    /// The number of columns and rows of samples.
+    size: UVec2,
+    /// The distance at each sample, row by row from the bottom left.
+    distances: Vec<f32>,
+}
+
+impl SignedDistanceField {
+    /// Bakes the signed distance to the nearest of `colliders`, which should be in world space, every
+    /// `cell_size` across `bounds`.
+    ///
+    /// Overlapping colliders are treated as their union. With no colliders, every distance is infinite. Cells
+    /// are never smaller than 1/1024 of the longest side of `bounds`.
+    pub fn bake(bounds: Rectangle, cell_size: f32, colliders: &[Collider]) -> Self {
+        let cell_size = cell_size.max(bounds.size().max_element() / MAX_CELLS_PER_SIDE);
+        let size = (bounds.size() / cell_size).ceil().max(Vec2::ONE).as_uvec2() + 1;
+        let aabbs: Vec<Rectangle> = colliders.iter().map(Collider::aabb).collect();
+
+        let distances = (0..size.y)
+            .flat_map(|y| (0..size.x).map(move |x| UVec2::new(x, y)))
+            .map(|sample| {
+                let point = bounds.min() + (sample.as_vec2() * cell_size);
+                colliders
+                    .iter()
+                    .zip(&aabbs)
+                    .fold(f32::INFINITY, |nearest, (collider, aabb)| {
+                        // Colliders whose bounding boxes are further away than the nearest so far can't be nearer.
+                        // Inside a collider, any other collider containing the point could be deeper still
+                        if nearest > 0.0 && aabb.distance_to_point(point) >= nearest {
+                            return nearest;
+                        }
+
+                        let (closest, is_inside, _) = collider.project_point(point);
+                        let distance = closest.distance(point);
+                        nearest.min(if is_inside { -distance } else { distance })
+                    })
+            })
+            .collect();
+
+        Self {
+            bounds,
+            cell_size,
+            size,
+            distances,
+        }
+    }
+
+    pub fn bounds(&self) -> Rectangle {
+        self.bounds
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    /// The number of columns and rows of samples.
+    pub fn size(&self) -> UVec2 {
+        self.size
+    }
+
+    /// The distance at every sample, row by row from the bottom left.
+    pub fn distances(&self) -> &[f32] {
+        &self.distances
+    }
+
+    /// The signed distance at `point`, bilinearly interpolated between the nearest samples.
+    ///
+    /// Points outside of the bounds are clamped onto them first. Returns infinity if nothing was baked.
+    pub fn sample(&self, point: Vec2) -> f32 {
+        let Some((cell, t)) = self.locate(point) else { return f32::INFINITY; };
+        let corners = self.corners(cell);
+        if corners.iter().any(|distance| distance.is_infinite()) {
+            return f32::INFINITY;
+        }
+
+        let [bottom_left, bottom_right, top_left, top_right] = corners;
+        let bottom = bottom_left + ((bottom_right - bottom_left) * t.x);
+        let top = top_left + ((top_right - top_left) * t.x);
+        bottom + ((top - bottom) * t.y)
+    }
+
+    /// The gradient of the bilinearly interpolated distance at `point`, which points away from the nearest
+    /// collider and is roughly unit length.
+    ///
+    /// Points outside of the bounds are clamped onto them first. Returns zero if nothing was baked, or where
+    /// the nearby samples are infinite.
+    pub fn gradient(&self, point: Vec2) -> Vec2 {
+        let Some((cell, t)) = self.locate(point) else { return Vec2::ZERO; };
+        let [bottom_left, bottom_right, top_left, top_right] = self.corners(cell);
+        let x = (bottom_right - bottom_left)
+            + (((top_right - top_left) - (bottom_right - bottom_left)) * t.y);
+        let y = (top_left - bottom_left)
+            + (((top_right - bottom_right) - (top_left - bottom_left)) * t.x);
+        let gradient = Vec2::new(x, y) / self.cell_size;
+        if gradient.is_finite() {
+            gradient
+        } else {
+            Vec2::ZERO
+        }
+    }
+
+    /// The cell containing `point`, clamped onto the bounds, and how far across that cell it lies from 0 to 1.
+    fn locate(&self, point: Vec2) -> Option<(UVec2, Vec2)> {
+        if self.distances.is_empty() {
+            return None;
+        }
+
+        let position = (point.clamp(self.bounds.min(), self.bounds.max()) - self.bounds.min())
+            / self.cell_size;
+        let cell = position
+            .floor()
+            .clamp(Vec2::ZERO, (self.size - 2).as_vec2());
+        Some((
+            cell.as_uvec2(),
+            (position - cell).clamp(Vec2::ZERO, Vec2::ONE),
+        ))
+    }
+
+    /// The samples at the bottom left, bottom right, top left and top right corners of `cell`.
+    fn corners(&self, cell: UVec2) -> [f32; 4] {
+        let index =
+            |x: u32, y: u32| self.distances[x as usize + (y as usize * self.size.x as usize)];
+        [
+            index(cell.x, cell.y),
+            index(cell.x + 1, cell.y),
+            index(cell.x, cell.y + 1),
+            index(cell.x + 1, cell.y + 1),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distances_are_negative_inside() {
+        let bounds = Rectangle::new(0.0, 0.0, 10.0, 10.0);
+        let circle = Collider::from(Circle::new(Vec2::new(5.0, 5.0), 2.0));
+        let sdf = SignedDistanceField::bake(bounds, 0.5, &[circle]);
+
+        assert!((sdf.sample(Vec2::new(9.0, 5.0)) - 2.0).abs() < 0.05);
+        assert!((sdf.sample(Vec2::new(5.0, 6.0)) + 1.0).abs() < 0.05);
+        assert!(sdf.sample(Vec2::new(5.0, 5.0)) < -1.5);
+        // Clamped onto the bounds
+        assert_eq!(
+            sdf.sample(Vec2::new(20.0, 5.0)),
+            sdf.sample(Vec2::new(10.0, 5.0))
+        );
+    }
+
+    #[test]
+    fn gradients_point_away_from_colliders() {
+        let bounds = Rectangle::new(0.0, 0.0, 10.0, 10.0);
+        let circle = Collider::from(Circle::new(Vec2::new(5.0, 5.0), 2.0));
+        let sdf = SignedDistanceField::bake(bounds, 0.5, &[circle]);
+
+        assert!(sdf.gradient(Vec2::new(8.25, 5.25)).x > 0.9);
+        assert!(sdf.gradient(Vec2::new(5.25, 1.75)).y < -0.9);
+        assert_eq!(
+            SignedDistanceField::default().gradient(Vec2::ZERO),
+            Vec2::ZERO
+        );
+    }
+
+    #[test]
+    fn overlapping_colliders_dont_depend_on_order() {
+        let bounds = Rectangle::new(0.0, 0.0, 10.0, 10.0);
+        let a = Collider::from(Circle::new(Vec2::new(4.0, 5.0), 2.0));
+        let b = Collider::from(Circle::new(Vec2::new(6.0, 5.0), 2.0));
+        let ab = SignedDistanceField::bake(bounds, 0.25, &[a.clone(), b.clone()]);
+        let ba = SignedDistanceField::bake(bounds, 0.25, &[b, a]);
+        let point = Vec2::new(5.75, 5.0);
+
+        assert!((ab.sample(point) + 1.75).abs() < 1e-4);
+        assert!((ba.sample(point) + 1.75).abs() < 1e-4);
+    }
+
+    #[test]
+    fn limits_cells_relative_to_the_bounds() {
+        let bounds = Rectangle::new(0.0, 0.0, 2048.0, 512.0);
+        let sdf = SignedDistanceField::bake(bounds, 0.0, &[]);
+
+        assert_eq!(sdf.cell_size(), 2.0);
+        assert_eq!(sdf.size(), UVec2::new(1025, 257));
+    }
+}
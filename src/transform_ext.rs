@@ -6,6 +6,22 @@ use bevy::{
 pub trait TransformPoint2 {
     fn transform_point2(&self, point: Vec2) -> Vec2;
     fn transform_vec2(&self, vec: Vec2) -> Vec2;
+
+    /// Transforms a surface normal, keeping it perpendicular to its surface even under non-uniform scaling.
+    ///
+    /// The result is normalized.
+    fn transform_normal2(&self, normal: Vec2) -> Vec2 {
+        // Normals are transformed by the inverse transpose of the linear part of the transform
+        let x_axis = self.transform_vec2(Vec2::X);
+        let y_axis = self.transform_vec2(Vec2::Y);
+        let determinant = x_axis.perp_dot(y_axis);
+
+        (Vec2::new(
+            (y_axis.y * normal.x) - (x_axis.y * normal.y),
+            (x_axis.x * normal.y) - (y_axis.x * normal.x),
+        ) * determinant.signum())
+        .normalize_or_zero()
+    }
 }
 
 impl TransformPoint2 for GlobalTransform {
@@ -0,0 +1,153 @@
+use super::{
+    collider::*,
+    layers::CollisionLayers,
+    query::{QueryFilter, SpatialQuery},
+};
+use bevy::{prelude::*, utils::HashSet};
+
+/// Tracks which colliders overlap the view rectangle of an orthographic camera, such as a `Camera2d`.
+///
+/// Insert it on the camera entity. It's updated every frame in the
+/// [`CollisionStage`](crate::plugin::CollisionStage), and a [`ViewEvent`] is sent whenever a collider enters or
+/// leaves the view. If the camera also has [`CollisionLayers`], only colliders on matching layers are tracked.
+#[derive(Component, Clone, Default, Debug, PartialEq)]
+pub struct CollidersInView(pub HashSet<Entity>);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ViewEvent {
+    Entered(ViewEntered),
+    Left(ViewLeft),
+}
+
+impl ViewEvent {
+    pub fn entered(camera: Entity, collider: Entity) -> Self {
+        Self::Entered(ViewEntered { camera, collider })
+    }
+
+    pub fn left(camera: Entity, collider: Entity) -> Self {
+        Self::Left(ViewLeft { camera, collider })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ViewEntered {
+    pub camera: Entity,
+    pub collider: Entity,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ViewLeft {
+    pub camera: Entity,
+    pub collider: Entity,
+}
+
+/// The area that an orthographic camera can see, in world space.
+pub fn view_collider(transform: &GlobalTransform, projection: &OrthographicProjection) -> Collider {
+    let rect = Rectangle::new(
+        projection.left * projection.scale,
+        projection.bottom * projection.scale,
+        projection.right * projection.scale,
+        projection.top * projection.scale,
+    );
+
+    // Go via a convex polygon so that rotated cameras are handled exactly, rather than by their bounding box
+    Collider::from(ConvexPolygon::from(rect)).to_transformed(transform)
+}
+
+pub(crate) fn update_colliders_in_view(
+    mut cameras: Query<(
+        Entity,
+        &GlobalTransform,
+        &OrthographicProjection,
+        Option<&CollisionLayers>,
+        &mut CollidersInView,
+    )>,
+    spatial_query: SpatialQuery,
+    mut events: EventWriter<ViewEvent>,
+) {
+    let mut events_batch = Vec::new();
+
+    for (camera, transform, projection, layers, mut in_view) in cameras.iter_mut() {
+        let layers = layers.copied().unwrap_or(QueryFilter::default().layers);
+        let filter = QueryFilter::new(layers).exclude([camera]);
+        let visible: HashSet<Entity> = spatial_query
+            .intersect_shape(
+                &view_collider(transform, projection),
+                &GlobalTransform::IDENTITY,
+                &filter,
+            )
+            .into_iter()
+            .collect();
+
+        if visible == in_view.0 {
+            continue;
+        }
+
+        events_batch.extend(
+            visible
+                .difference(&in_view.0)
+                .map(|&collider| ViewEvent::entered(camera, collider)),
+        );
+        events_batch.extend(
+            in_view
+                .0
+                .difference(&visible)
+                .map(|&collider| ViewEvent::left(camera, collider)),
+        );
+
+        in_view.0 = visible;
+    }
+
+    events.send_batch(events_batch);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        layers::CollisionLayerFlags,
+        plugin::{add_collision_stage, ColliderBundle},
+    };
+
+    fn spawn_circle(app: &mut App, x: f32, layers: CollisionLayers) -> Entity {
+        app.world
+            .spawn(ColliderBundle {
+                transform: TransformBundle {
+                    local: Transform::from_xyz(x, 0.0, 0.0),
+                    global: Transform::from_xyz(x, 0.0, 0.0).into(),
+                },
+                layers,
+                ..ColliderBundle::circle(1.0)
+            })
+            .id()
+    }
+
+    #[test]
+    fn only_tracks_colliders_on_the_camera_layers() {
+        let mut app = App::new();
+        add_collision_stage(&mut app);
+        let flags = CollisionLayerFlags::from_bits_truncate(1);
+        let visible = spawn_circle(&mut app, 0.0, CollisionLayers::Exclusive(flags));
+        spawn_circle(&mut app, 20.0, CollisionLayers::Exclusive(flags));
+        spawn_circle(&mut app, 2.0, CollisionLayers::Exclusive(!flags));
+        let camera = app
+            .world
+            .spawn((
+                GlobalTransform::IDENTITY,
+                OrthographicProjection {
+                    left: -5.0,
+                    right: 5.0,
+                    bottom: -5.0,
+                    top: 5.0,
+                    ..default()
+                },
+                CollisionLayers::Inclusive(flags),
+                CollidersInView::default(),
+            ))
+            .id();
+        app.update();
+
+        let in_view = app.world.get::<CollidersInView>(camera).unwrap();
+        assert_eq!(in_view.0, HashSet::from_iter([visible]));
+    }
+}